@@ -0,0 +1,78 @@
+//! Arithmetic on answers that reports overflow as a [`SolveError`] instead of panicking or wrapping.
+
+use crate::SolveError;
+
+/// The integer types answers are computed in.
+pub trait Integer: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: $t) -> Option<$t> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                <$t>::checked_mul(self, other)
+            }
+        })*
+    };
+}
+
+integer!(u32, u64, u128, usize, i32, i64, i128, isize);
+
+/// The error of a value that does not fit in `T`.
+pub fn overflow<T>() -> SolveError {
+    SolveError::no_answer(format!("the answer does not fit in a {}", std::any::type_name::<T>()))
+}
+
+pub fn add<T: Integer>(a: T, b: T) -> Result<T, SolveError> {
+    a.checked_add(b).ok_or_else(overflow::<T>)
+}
+
+pub fn sub<T: Integer>(a: T, b: T) -> Result<T, SolveError> {
+    a.checked_sub(b).ok_or_else(overflow::<T>)
+}
+
+pub fn mul<T: Integer>(a: T, b: T) -> Result<T, SolveError> {
+    a.checked_mul(b).ok_or_else(overflow::<T>)
+}
+
+pub fn sum<T: Integer>(values: impl IntoIterator<Item = T>) -> Result<T, SolveError> {
+    return values.into_iter().try_fold(T::ZERO, add);
+}
+
+pub fn product<T: Integer>(values: impl IntoIterator<Item = T>) -> Result<T, SolveError> {
+    return values.into_iter().try_fold(T::ONE, mul);
+}
+
+#[cfg(test)]
+mod test_checked {
+    use super::*;
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(sum([1u64, 2, 3]), Ok(6));
+        assert_eq!(sum([u64::MAX, 1]), Err(SolveError::no_answer("the answer does not fit in a u64")));
+        assert_eq!(product([2usize, 3, 7]), Ok(42));
+        assert_eq!(product(Vec::<usize>::new()), Ok(1));
+        assert!(product([usize::MAX, 2]).is_err());
+        assert!(sum([isize::MIN, -1]).is_err());
+        assert_eq!(sub(3u32, 5), Err(overflow::<u32>()));
+        assert_eq!(sub(3i32, 5), Ok(-2));
+    }
+}
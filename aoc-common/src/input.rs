@@ -0,0 +1,130 @@
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Environment variable pointing to a directory holding the inputs of all days,
+/// laid out as `$AOC_INPUT_DIR/dayNN/input.txt`.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+/// The directory the input files of a single day are read from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Inputs {
+    dir: PathBuf,
+}
+
+impl Inputs {
+    /// Uses `$AOC_INPUT_DIR/<crate_name>` when the environment variable is set,
+    /// and the `resources` directory of the crate otherwise.
+    pub fn for_crate(crate_name: &str, crate_dir: impl AsRef<Path>) -> Inputs {
+        return match std::env::var_os(INPUT_DIR_ENV) {
+            Some(dir) => Inputs::in_dir(dir, crate_name),
+            None => Inputs { dir: crate_dir.as_ref().join("resources") },
+        };
+    }
+
+    /// Uses `<dir>/<crate_name>`, following the layout of `$AOC_INPUT_DIR`.
+    pub fn in_dir(dir: impl AsRef<Path>, crate_name: &str) -> Inputs {
+        Inputs { dir: dir.as_ref().join(crate_name) }
+    }
+
+    pub fn path(self: &Inputs, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    pub fn read(self: &Inputs, name: &str) -> Result<String, InputError> {
+        read_file(self.path(name))
+    }
+}
+
+/// Reads a file, or stdin when the path is `-`.
+pub fn read_file(path: impl AsRef<Path>) -> Result<String, InputError> {
+    let path = path.as_ref();
+    if path == Path::new("-") {
+        return read_stdin();
+    }
+    return std::fs::read_to_string(path).map_err(|source| match source.kind() {
+        std::io::ErrorKind::NotFound => InputError::Missing { path: path.to_path_buf() },
+        _ => InputError::Io { path: path.to_path_buf(), source },
+    });
+}
+
+pub fn read_stdin() -> Result<String, InputError> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).map_err(InputError::Stdin)?;
+    return Ok(input);
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing { path: PathBuf },
+    Io { path: PathBuf, source: std::io::Error },
+    Stdin(std::io::Error),
+    /// Downloading the input failed, `message` says why.
+    Fetch { url: String, message: String },
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing { path } => write!(
+                f,
+                "input file {} does not exist, put it there or point {INPUT_DIR_ENV} to a directory holding the inputs",
+                path.display()
+            ),
+            InputError::Io { path, source } => write!(f, "could not read input file {}: {source}", path.display()),
+            InputError::Stdin(source) => write!(f, "could not read input from stdin: {source}"),
+            InputError::Fetch { url, message } => write!(f, "could not fetch input from {url}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Missing { .. } | InputError::Fetch { .. } => None,
+            InputError::Io { source, .. } | InputError::Stdin(source) => Some(source),
+        }
+    }
+}
+
+/// The `Inputs` of the crate this macro is used in.
+#[macro_export]
+macro_rules! inputs {
+    () => {
+        $crate::input::Inputs::for_crate(env!("CARGO_PKG_NAME"), env!("CARGO_MANIFEST_DIR"))
+    };
+}
+
+/// Reads an example file of the current crate inside a test. The examples are not checked in, so the
+/// tests reading them are marked `#[ignore]` and fail with the missing path when run without them.
+#[macro_export]
+macro_rules! example {
+    ($name:expr) => {
+        match $crate::inputs!().read($name) {
+            Ok(input) => input,
+            Err(err) => panic!("missing example, place it in the resources of the day: {err}"),
+        }
+    };
+}
+
+#[cfg(test)]
+mod test_input {
+    use super::*;
+
+    #[test]
+    fn test_missing_file() {
+        let inputs = Inputs::in_dir("/nonexistent", "day01");
+        assert_eq!(inputs.path("input.txt"), PathBuf::from("/nonexistent/day01/input.txt"));
+        let err = inputs.read("input.txt").unwrap_err();
+        assert!(matches!(err, InputError::Missing { .. }));
+        assert!(err.to_string().contains("/nonexistent/day01/input.txt"));
+    }
+
+    #[test]
+    fn test_read_file() {
+        let path = std::env::temp_dir().join(format!("aoc-common-{}.txt", std::process::id()));
+        std::fs::write(&path, "1abc2").unwrap();
+        assert_eq!(read_file(&path).unwrap(), "1abc2");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+use std::process::ExitCode;
+
+pub mod checked;
+pub mod input;
+pub mod parallel;
+pub mod parse;
+
+pub use input::{InputError, Inputs};
+pub use parse::ParseError;
+
+/// The shape every day's solution follows: the raw input is parsed once,
+/// after which both parts are computed from the parsed representation.
+pub trait Solution {
+    /// Day of December the puzzle was released on.
+    const DAY: u8;
+
+    /// Knobs a part can be tuned with, like the expansion factor of day 11.
+    /// Days without any use `()`.
+    type Params: Default;
+
+    /// The parsed puzzle input, which may borrow from the raw input.
+    type Input<'a>;
+
+    type Answer: Display;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError>;
+
+    fn part1(input: &Self::Input<'_>, params: &Self::Params) -> Result<Self::Answer, SolveError>;
+
+    fn part2(input: &Self::Input<'_>, params: &Self::Params) -> Result<Self::Answer, SolveError>;
+
+    /// The parameters the binaries and the runner solve with. Days that can be tuned read them from
+    /// the environment, the others use the defaults.
+    fn params_from_env() -> Result<Self::Params, String> {
+        Ok(Self::Params::default())
+    }
+
+    /// The span the parsing and the parts of the day are traced in.
+    fn span() -> tracing::Span {
+        tracing::info_span!("day", day = Self::DAY)
+    }
+
+    /// [`Solution::parse`] in a `parse` span.
+    fn parse_traced(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        tracing::info_span!("parse").in_scope(|| Self::parse(input))
+    }
+
+    /// [`Solution::part1`] in a `part1` span.
+    fn part1_traced(input: &Self::Input<'_>, params: &Self::Params) -> Result<Self::Answer, SolveError> {
+        tracing::info_span!("part1").in_scope(|| Self::part1(input, params))
+    }
+
+    /// [`Solution::part2`] in a `part2` span.
+    fn part2_traced(input: &Self::Input<'_>, params: &Self::Params) -> Result<Self::Answer, SolveError> {
+        tracing::info_span!("part2").in_scope(|| Self::part2(input, params))
+    }
+
+    /// Parses `input` and solves part 1 with the default parameters.
+    fn solve_part1(input: &str) -> Result<Self::Answer, SolveError> {
+        let _day = Self::span().entered();
+        Self::part1_traced(&Self::parse_traced(input)?, &Self::Params::default())
+    }
+
+    /// Parses `input` and solves part 2 with the default parameters.
+    fn solve_part2(input: &str) -> Result<Self::Answer, SolveError> {
+        let _day = Self::span().entered();
+        Self::part2_traced(&Self::parse_traced(input)?, &Self::Params::default())
+    }
+}
+
+/// Why a part of a puzzle input could not be answered.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SolveError {
+    Parse(ParseError),
+    /// The input is well-formed, but has no answer, or one too big to compute.
+    NoAnswer(String),
+    /// The parameters could not be read from the environment.
+    Params(String),
+}
+
+impl SolveError {
+    pub fn no_answer(message: impl Into<String>) -> SolveError {
+        SolveError::NoAnswer(message.into())
+    }
+
+    /// Like the message of the error, showing where in `input` it could not be parsed.
+    pub fn render(self: &SolveError, input: &str) -> String {
+        return match self {
+            SolveError::Parse(err) => format!("invalid input: {}", err.render(input)),
+            SolveError::NoAnswer(_) | SolveError::Params(_) => self.to_string(),
+        };
+    }
+}
+
+impl From<ParseError> for SolveError {
+    fn from(err: ParseError) -> SolveError {
+        SolveError::Parse(err)
+    }
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::Parse(err) => write!(f, "invalid input: {err}"),
+            SolveError::NoAnswer(message) => write!(f, "no answer: {message}"),
+            SolveError::Params(message) => write!(f, "invalid parameters: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Entry point of the per-day binaries: solves both parts of the input given as first argument (`-` for
+/// stdin), or of `input.txt` from `inputs` otherwise, with the parameters of [`Solution::params_from_env`].
+pub fn run<S: Solution>(inputs: Inputs) -> ExitCode {
+    return match S::params_from_env() {
+        Ok(params) => run_with::<S>(inputs, &params),
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    };
+}
+
+/// Like [`run`], solving the parts with `params` instead of the defaults.
+pub fn run_with<S: Solution>(inputs: Inputs, params: &S::Params) -> ExitCode {
+    let input = match std::env::args_os().nth(1) {
+        Some(path) => input::read_file(path),
+        None => inputs.read("input.txt"),
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let _day = S::span().entered();
+    let parsed = match S::parse_traced(&input) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("error: invalid input: {}", err.render(&input));
+            return ExitCode::FAILURE;
+        }
+    };
+    for (number, answer) in [(1, S::part1_traced(&parsed, params)), (2, S::part2_traced(&parsed, params))] {
+        match answer {
+            Ok(answer) => println!("part {number}: {answer}"),
+            Err(err) => {
+                eprintln!("error: part {number}: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    return ExitCode::SUCCESS;
+}
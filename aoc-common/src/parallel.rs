@@ -0,0 +1,80 @@
+//! Work on independent items, like the lines of an input. With the `parallel` feature the items are
+//! spread over all cores, without it they are handled one by one. Either way the results are the same.
+
+use std::iter::Sum;
+
+use crate::checked::{self, Integer};
+use crate::{ParseError, SolveError};
+
+/// Applies `f` to every item, keeping the results in the order of `items`.
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).collect();
+    }
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).collect();
+}
+
+/// Sums `f` over every item.
+pub fn sum<T: Sync, R: Send + Sum>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> R {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        return items.par_iter().map(f).sum();
+    }
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).sum();
+}
+
+/// Sums `f` over every item, or returns the error of the first item that fails, like the serial sum.
+/// Fails as well when the items fit but their sum does not.
+pub fn try_sum<T: Sync, R: Integer + Send>(
+    items: &[T],
+    f: impl Fn(&T) -> Result<R, SolveError> + Sync + Send,
+) -> Result<R, SolveError> {
+    let values = map(items, f).into_iter().collect::<Result<Vec<R>, SolveError>>()?;
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        return values.into_par_iter().map(Ok).try_reduce(|| R::ZERO, checked::add);
+    }
+    #[cfg(not(feature = "parallel"))]
+    return checked::sum(values);
+}
+
+/// Like [`crate::parse::parse_lines`], reporting the error of the first line that fails to parse
+/// even when later lines were parsed first.
+pub fn parse_lines<'a, T: Send>(
+    input: &'a str,
+    parse_line: impl Fn(&'a str) -> Result<T, ParseError> + Sync + Send,
+) -> Result<Vec<T>, ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+    return map(&lines, |line| parse_line(line).map_err(|err| err.within(input, line))).into_iter().collect();
+}
+
+#[cfg(test)]
+mod test_parallel {
+    use super::*;
+    use crate::parse::parse_number;
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items: Vec<u64> = (0..10_000).collect();
+        assert_eq!(map(&items, |item| item * 2), items.iter().map(|item| item * 2).collect::<Vec<_>>());
+        assert_eq!(sum(&items, |item| *item), 49_995_000);
+        let checked = |item: &u64| if item % 3000 == 2999 { Err(SolveError::no_answer(item.to_string())) } else { Ok(*item) };
+        assert_eq!(try_sum(&items, checked), Err(SolveError::no_answer("2999")));
+        assert_eq!(try_sum(&[u64::MAX / 2 + 1, u64::MAX / 2 + 1], |item| Ok(*item)), Err(checked::overflow::<u64>()));
+    }
+
+    #[test]
+    fn test_parse_lines_matches_serial() {
+        let input = (0..1000).map(|line| if line % 300 == 299 { "x".to_string() } else { line.to_string() }).collect::<Vec<_>>().join("\n");
+        let parse = |line: &str| parse_number::<u32>(line, line);
+        assert_eq!(parse_lines(&input, parse), crate::parse::parse_lines(&input, parse));
+        assert_eq!(parse_lines(&input, parse).unwrap_err().line, 300);
+        assert_eq!(parse_lines("1\n2", parse), Ok(vec![1, 2]));
+    }
+}
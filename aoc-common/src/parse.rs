@@ -0,0 +1,204 @@
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "nom")]
+use nom_supreme::error::{BaseErrorKind, ErrorTree, Expectation, StackContext};
+
+/// A piece of puzzle input that could not be parsed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    /// Line of the input the error was found on, starting at 1.
+    pub line: usize,
+    /// Column within that line in characters, starting at 1.
+    pub column: usize,
+    /// The offending text.
+    pub text: String,
+    pub message: String,
+    /// What was being parsed when the error occurred, innermost first.
+    pub context: Vec<String>,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: impl Into<String>, message: impl Into<String>) -> ParseError {
+        ParseError { line, column, text: text.into(), message: message.into(), context: vec![] }
+    }
+
+    /// Creates an error for `fragment`, which must be a slice of `input`,
+    /// deriving its line and column from where `fragment` starts in `input`.
+    pub fn locate(input: &str, fragment: &str, message: impl Into<String>) -> ParseError {
+        let (line, column) = position(input, fragment);
+        ParseError { line, column, text: fragment.to_string(), message: message.into(), context: vec![] }
+    }
+
+    /// Adds an outer piece of context, like the section of the input being parsed.
+    pub fn with_context(self: ParseError, context: impl Into<String>) -> ParseError {
+        let mut err = self;
+        err.context.push(context.into());
+        return err;
+    }
+
+    /// Renders the error together with the offending line of `input`,
+    /// with a caret under the column the error occurred at. Line or column 0 lack either.
+    pub fn render(self: &ParseError, input: &str) -> String {
+        let Some(line) = self.line.checked_sub(1).and_then(|index| input.lines().nth(index)) else {
+            return self.to_string();
+        };
+        let gutter = " ".repeat(self.line.to_string().len());
+        let rendered = format!("{self}\n{gutter} |\n{} | {line}", self.line);
+        return match self.column.checked_sub(1) {
+            Some(before) => format!("{rendered}\n{gutter} | {}^", " ".repeat(before)),
+            None => rendered,
+        };
+    }
+
+    /// Turns an error found while parsing `inner` on its own into one relative to `outer`,
+    /// of which `inner` must be a slice.
+    pub fn within(self: ParseError, outer: &str, inner: &str) -> ParseError {
+        let (line, column) = position(outer, inner);
+        return if self.line == 1 {
+            ParseError { line, column: (column + self.column).saturating_sub(1), ..self }
+        } else {
+            ParseError { line: (line + self.line).saturating_sub(1), ..self }
+        };
+    }
+}
+
+/// Line and column, both starting at 1, at which `fragment` starts in `input`.
+/// A `fragment` that is not a slice of `input` is placed at its start.
+fn position(input: &str, fragment: &str) -> (usize, usize) {
+    let offset = (fragment.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+    let offset = if offset <= input.len() && input.is_char_boundary(offset) { offset } else { 0 };
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    return (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1);
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)?;
+        for context in &self.context {
+            write!(f, " in {context}")?;
+        }
+        if !self.text.is_empty() {
+            write!(f, " (found `{}`)", self.text)?;
+        }
+        return Ok(());
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every line of `input` with `parse_line`, stopping at the first error
+/// and reporting it at the line it occurred on.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    parse_line: impl Fn(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    return input.lines()
+        .map(|line| parse_line(line).map_err(|err| err.within(input, line)))
+        .collect();
+}
+
+/// Parses `text` as a number, reporting it relative to `input` when it is not one.
+pub fn parse_number<T: std::str::FromStr>(input: &str, text: &str) -> Result<T, ParseError> {
+    text.parse::<T>().map_err(|_| ParseError::locate(input, text, "expected a number"))
+}
+
+/// Converts the error of a nom parser run on `input` into a `ParseError`,
+/// keeping the context attached through `nom_supreme::ParserExt::context`.
+#[cfg(feature = "nom")]
+pub fn from_nom(input: &str, err: nom::Err<ErrorTree<&str>>) -> ParseError {
+    return match err {
+        nom::Err::Incomplete(_) => ParseError::locate(input, &input[input.len()..], "unexpected end of input"),
+        nom::Err::Error(tree) | nom::Err::Failure(tree) => from_error_tree(input, tree),
+    };
+}
+
+#[cfg(feature = "nom")]
+fn from_error_tree(input: &str, tree: ErrorTree<&str>) -> ParseError {
+    return match tree {
+        ErrorTree::Base { location, kind } => {
+            let message = match kind {
+                BaseErrorKind::Expected(Expectation::Eof) => "expected the end of the input".to_string(),
+                BaseErrorKind::Expected(expectation) => format!("expected {expectation}"),
+                BaseErrorKind::Kind(kind) => format!("could not parse input ({})", kind.description()),
+                BaseErrorKind::External(err) => err.to_string(),
+            };
+            ParseError::locate(input, location.lines().next().unwrap_or(location), message)
+        }
+        ErrorTree::Stack { base, contexts } => contexts.into_iter()
+            .filter_map(|(_, context)| match context {
+                StackContext::Context(context) => Some(context),
+                StackContext::Kind(_) => None,
+            })
+            .fold(from_error_tree(input, *base), |err, context| err.with_context(context)),
+        ErrorTree::Alt(alternatives) => {
+            // Report the alternatives that got furthest into the input, as those are most likely what was meant.
+            let errors: Vec<ParseError> = alternatives.into_iter().map(|tree| from_error_tree(input, tree)).collect();
+            let furthest = errors.iter().map(|err| (err.line, err.column)).max().unwrap_or((1, 1));
+            let mut errors = errors.into_iter().filter(|err| (err.line, err.column) == furthest);
+            let first = errors.next().unwrap_or_else(|| ParseError::new(1, 1, "", "could not parse input"));
+            errors.fold(first, |err, other| {
+                let other = other.message.strip_prefix("expected ").unwrap_or(&other.message).to_string();
+                ParseError { message: format!("{} or {other}", err.message), ..err }
+            })
+        }
+    };
+}
+
+#[cfg(test)]
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn test_locate() {
+        let input = "Game 1: 3 blue\nGame 2: 4 purple";
+        let fragment = &input[25..31];
+        assert_eq!(fragment, "purple");
+        let err = ParseError::locate(input, fragment, "invalid color");
+        assert_eq!((err.line, err.column), (2, 11));
+        assert_eq!(err.to_string(), "line 2, column 11: invalid color (found `purple`)");
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 x 48";
+        let err = ParseError::locate(input, &input[43..44], "expected a number").with_context("seed-to-soil map");
+        assert_eq!(err.render(input), [
+            "line 5, column 4: expected a number in seed-to-soil map (found `x`)",
+            "  |",
+            "5 | 52 x 48",
+            "  |    ^",
+        ].join("\n"));
+    }
+
+    #[test]
+    fn test_render_without_position() {
+        let input = "52 x 48";
+        let err = ParseError::new(0, 4, "x", "expected a number");
+        assert_eq!(err.render(input), "line 0, column 4: expected a number (found `x`)");
+        let err = ParseError::new(1, 0, "x", "expected a number");
+        assert_eq!(err.render(input), "line 1, column 0: expected a number (found `x`)\n  |\n1 | 52 x 48");
+        assert_eq!(ParseError::new(9, 1, "", "too short").render(input), "line 9, column 1: too short");
+    }
+
+    #[test]
+    fn test_locate_unrelated_fragment() {
+        let err = ParseError::locate("abc", &String::from("def"), "unexpected");
+        assert_eq!((err.line, err.column), (1, 1));
+    }
+
+    #[test]
+    fn test_within() {
+        let input = "Game 1: 3 blue\nGame 2: 4 purple";
+        let line = &input[15..];
+        let err = ParseError::locate(line, &line[10..], "invalid color").within(input, line);
+        assert_eq!((err.line, err.column), (2, 11));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let input = "1\n2\nx\n4";
+        assert_eq!(parse_lines(input, |line| parse_number::<u8>(line, line)).unwrap_err(), ParseError::new(3, 1, "x", "expected a number"));
+        assert_eq!(parse_lines("1\n2", |line| parse_number::<u8>(line, line)), Ok(vec![1, 2]));
+    }
+}
@@ -0,0 +1,101 @@
+/// Finds the cycle that repeatedly applying `step` to `start` ends up in, with Floyd's tortoise and hare.
+/// Returns `(prefix, period)`: the number of steps before entering the cycle and the length of the cycle.
+pub fn floyd<T: Clone + Eq>(start: T, mut step: impl FnMut(&T) -> T) -> (usize, usize) {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+    // The hare is now a multiple of the period ahead, so both meet at the start of the cycle.
+    let mut prefix = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+    return (prefix, period);
+}
+
+/// Like [`floyd`], with Brent's algorithm, which usually needs fewer steps.
+pub fn brent<T: Clone + Eq>(start: T, mut step: impl FnMut(&T) -> T) -> (usize, usize) {
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+    let mut prefix = 0;
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    return (prefix, period);
+}
+
+#[cfg(test)]
+mod test_cycle {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Remembers every value until one repeats.
+    fn oracle(start: u64, step: impl Fn(&u64) -> u64) -> (usize, usize) {
+        let mut seen = HashMap::new();
+        let mut current = start;
+        for index in 0.. {
+            if let Some(first) = seen.insert(current, index) {
+                return (first, index - first);
+            }
+            current = step(&current);
+        }
+        unreachable!();
+    }
+
+    #[test]
+    fn test_against_oracle() {
+        for modulus in 1..40 {
+            for start in 0..modulus {
+                let step = |value: &u64| (value * value + 1) % modulus;
+                assert_eq!(floyd(start, step), oracle(start, step), "floyd from {start} mod {modulus}");
+                assert_eq!(brent(start, step), oracle(start, step), "brent from {start} mod {modulus}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pure_cycle() {
+        assert_eq!(floyd(0, |value| (value + 1) % 7), (0, 7));
+        assert_eq!(brent(0, |value| (value + 1) % 7), (0, 7));
+        assert_eq!(brent('x', |_| 'x'), (0, 1));
+    }
+
+    #[test]
+    fn test_prefix() {
+        // 10, 9, .., 1, 0, then 0 to 2 over and over.
+        let step = |value: &u32| if *value > 2 { value - 1 } else { (value + 1) % 3 };
+        assert_eq!(floyd(10, step), (8, 3));
+        assert_eq!(brent(10, step), (8, 3));
+    }
+}
@@ -0,0 +1,8 @@
+//! Graph algorithms over implicit graphs: nodes are any hashable value and edges are given by a
+//! successor function, so puzzles never have to build the graph up front.
+
+pub mod cycle;
+pub mod search;
+
+pub use cycle::{brent, floyd};
+pub use search::{astar, bfs, bfs_reach, dfs, dfs_reach, dijkstra};
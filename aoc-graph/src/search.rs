@@ -0,0 +1,260 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The nodes seen by a search, each with the index of the node it was reached from.
+struct Visited<N> {
+    nodes: Vec<N>,
+    parents: Vec<usize>,
+    indices: HashMap<N, usize>,
+}
+
+impl<N: Eq + Hash + Clone> Visited<N> {
+    fn new(start: N) -> Visited<N> {
+        return Visited { nodes: vec![start.clone()], parents: vec![0], indices: HashMap::from([(start, 0)]) };
+    }
+
+    /// Records `node` as reached from `parent`, returning its index unless it was seen before.
+    fn insert(self: &mut Visited<N>, node: N, parent: usize) -> Option<usize> {
+        return match self.indices.entry(node) {
+            Entry::Occupied(_) => None,
+            Entry::Vacant(entry) => {
+                let index = self.nodes.len();
+                self.nodes.push(entry.key().clone());
+                self.parents.push(parent);
+                entry.insert(index);
+                Some(index)
+            }
+        };
+    }
+
+    /// The nodes from the start up to and including the node at `index`.
+    fn path(self: &Visited<N>, index: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[index].clone()];
+        let mut index = index;
+        while index != 0 {
+            index = self.parents[index];
+            path.push(self.nodes[index].clone());
+        }
+        path.reverse();
+        return path;
+    }
+}
+
+/// A shortest path, counted in steps, from `start` to a node satisfying `is_goal`, both ends included.
+pub fn bfs<N, I>(start: N, mut successors: impl FnMut(&N) -> I, mut is_goal: impl FnMut(&N) -> bool) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new(start);
+    let mut queue = VecDeque::from([0]);
+    while let Some(index) = queue.pop_front() {
+        if is_goal(&visited.nodes[index]) {
+            return Some(visited.path(index));
+        }
+        for next in successors(&visited.nodes[index]) {
+            queue.extend(visited.insert(next, index));
+        }
+    }
+    return None;
+}
+
+/// Every node reachable from `start` with the number of steps it takes to get there, nearest first.
+pub fn bfs_reach<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> Vec<(N, usize)>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new(start);
+    let mut distances = vec![0];
+    let mut queue = VecDeque::from([0]);
+    while let Some(index) = queue.pop_front() {
+        for next in successors(&visited.nodes[index]) {
+            if let Some(next) = visited.insert(next, index) {
+                distances.push(distances[index] + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    return visited.nodes.into_iter().zip(distances).collect();
+}
+
+/// The nodes reachable from `start` in depth-first order, taking the successors in the order they are given.
+fn dfs_order<N, I>(start: N, mut successors: impl FnMut(&N) -> I, mut is_goal: impl FnMut(&N) -> bool) -> (Visited<N>, Option<usize>)
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited { nodes: vec![], parents: vec![], indices: HashMap::new() };
+    let mut stack = vec![(start, 0)];
+    while let Some((node, parent)) = stack.pop() {
+        if visited.indices.contains_key(&node) {
+            continue;
+        }
+        let index = visited.nodes.len();
+        visited.nodes.push(node.clone());
+        visited.parents.push(parent);
+        visited.indices.insert(node, index);
+        if is_goal(&visited.nodes[index]) {
+            return (visited, Some(index));
+        }
+        let mut next: Vec<(N, usize)> = successors(&visited.nodes[index]).into_iter()
+            .filter(|next| !visited.indices.contains_key(next))
+            .map(|next| (next, index))
+            .collect();
+        next.reverse();
+        stack.append(&mut next);
+    }
+    return (visited, None);
+}
+
+/// A path, not necessarily the shortest, from `start` to a node satisfying `is_goal`, both ends included.
+pub fn dfs<N, I>(start: N, successors: impl FnMut(&N) -> I, is_goal: impl FnMut(&N) -> bool) -> Option<Vec<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let (visited, goal) = dfs_order(start, successors, is_goal);
+    return goal.map(|goal| visited.path(goal));
+}
+
+/// Every node reachable from `start`, in the order a depth-first search visits them.
+/// Following a path or a loop this gives its nodes in order.
+pub fn dfs_reach<N, I>(start: N, successors: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    return dfs_order(start, successors, |_| false).0.nodes;
+}
+
+/// A cheapest path from `start` to a node satisfying `is_goal`, with its cost. Successors come with the
+/// cost of the step to them, which must not be negative.
+pub fn dijkstra<N, C, I>(start: N, successors: impl FnMut(&N) -> I, is_goal: impl FnMut(&N) -> bool) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    return astar(start, successors, |_| C::default(), is_goal);
+}
+
+/// Like [`dijkstra`], guided by a `heuristic` estimating the remaining cost from a node to a goal.
+/// The path is only the cheapest when the heuristic never overestimates.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut visited = Visited::new(start);
+    let mut costs = vec![C::default()];
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        if cost > costs[index] {
+            continue;
+        }
+        if is_goal(&visited.nodes[index]) {
+            return Some((visited.path(index), cost));
+        }
+        for (next, step) in successors(&visited.nodes[index]) {
+            let next_cost = cost + step;
+            let next_index = match visited.indices.get(&next) {
+                Some(&seen) if next_cost >= costs[seen] => continue,
+                Some(&seen) => {
+                    visited.parents[seen] = index;
+                    costs[seen] = next_cost;
+                    seen
+                }
+                None => {
+                    costs.push(next_cost);
+                    visited.insert(next, index).unwrap()
+                }
+            };
+            queue.push(Reverse((next_cost + heuristic(&visited.nodes[next_index]), next_cost, next_index)));
+        }
+    }
+    return None;
+}
+
+#[cfg(test)]
+mod test_search {
+    use super::*;
+
+    /// A small grid where `#` blocks the way, walked orthogonally.
+    const MAZE: [&str; 4] = [
+        "..#.",
+        ".##.",
+        "....",
+        "#.#.",
+    ];
+
+    fn open(x: i32, y: i32) -> bool {
+        return (0..4).contains(&x) && (0..4).contains(&y) && MAZE[y as usize].as_bytes()[x as usize] == b'.';
+    }
+
+    fn steps(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        return [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)].into_iter().filter(|&(x, y)| open(x, y)).collect();
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs((0, 0), steps, |&node| node == (3, 0)).unwrap();
+        assert_eq!(path, [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (3, 2), (3, 1), (3, 0)]);
+        assert_eq!(bfs((0, 0), steps, |&node| node == (2, 0)), None);
+        assert_eq!(bfs((0, 0), steps, |&node| node == (0, 0)), Some(vec![(0, 0)]));
+    }
+
+    #[test]
+    fn test_bfs_reach() {
+        let reached = bfs_reach((0, 0), steps);
+        assert_eq!(reached.len(), 11);
+        assert_eq!(reached.iter().find(|(node, _)| *node == (3, 3)), Some(&((3, 3), 6)));
+        assert!(reached.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+
+    #[test]
+    fn test_dfs() {
+        let path = dfs((0, 0), steps, |&node| node == (3, 0)).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(3, 0)));
+        assert!(path.windows(2).all(|pair| steps(&pair[0]).contains(&pair[1])));
+        assert_eq!(dfs((0, 0), steps, |&node| node == (2, 0)), None);
+    }
+
+    #[test]
+    fn test_dfs_reach_follows_a_loop() {
+        let ring = |&node: &u8| [(node + 1) % 6, (node + 5) % 6];
+        assert_eq!(dfs_reach(0, ring), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // The direct edge is dearer than going around.
+        let edges = |&node: &char| match node {
+            'a' => vec![('b', 1), ('d', 10)],
+            'b' => vec![('c', 2)],
+            'c' => vec![('d', 3)],
+            _ => vec![],
+        };
+        assert_eq!(dijkstra('a', edges, |&node| node == 'd'), Some((vec!['a', 'b', 'c', 'd'], 6)));
+        assert_eq!(dijkstra('d', edges, |&node| node == 'a'), None);
+    }
+
+    #[test]
+    fn test_astar_matches_bfs() {
+        let weighted = |node: &(i32, i32)| steps(node).into_iter().map(|next| (next, 1));
+        let distance = |&(x, y): &(i32, i32)| (3 - x).abs() + y;
+        let (path, cost) = astar((0, 0), weighted, distance, |&node| node == (3, 0)).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.len(), bfs((0, 0), steps, |&node| node == (3, 0)).unwrap().len());
+    }
+}
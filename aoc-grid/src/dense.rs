@@ -0,0 +1,205 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use aoc_common::ParseError;
+
+use crate::Point;
+
+/// A rectangular grid storing every cell, row by row.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Panics when the rows are not all of the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == width), "rows of a grid must be of the same length");
+        let height = rows.len();
+        return Grid { width, height, cells: rows.into_iter().flatten().collect() };
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Point) -> T) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x as i32, y as i32)))
+            .map(&mut cell)
+            .collect();
+        return Grid { width, height, cells };
+    }
+
+    /// Parses every character of `input` into a cell with `cell`. All lines have to be equally long,
+    /// and a cell that fails to parse is reported at its position in `input`.
+    pub fn parse<E: Into<String>>(input: &str, mut cell: impl FnMut(char) -> Result<T, E>) -> Result<Grid<T>, ParseError> {
+        let width = input.lines().next().map_or(0, |line| line.chars().count());
+        let mut cells = vec![];
+        let mut height = 0;
+        for line in input.lines() {
+            if line.chars().count() != width {
+                return Err(ParseError::locate(input, line, format!("expected a row of {width} cells")));
+            }
+            for (index, c) in line.char_indices() {
+                let parsed = cell(c).map_err(|message| ParseError::locate(input, &line[index..index + c.len_utf8()], message))?;
+                cells.push(parsed);
+            }
+            height += 1;
+        }
+        return Ok(Grid { width, height, cells });
+    }
+
+    pub fn width(self: &Grid<T>) -> usize {
+        self.width
+    }
+
+    pub fn height(self: &Grid<T>) -> usize {
+        self.height
+    }
+
+    pub fn contains(self: &Grid<T>, point: Point) -> bool {
+        return point.x >= 0 && point.y >= 0 && (point.x as usize) < self.width && (point.y as usize) < self.height;
+    }
+
+    fn index_of(self: &Grid<T>, point: Point) -> Option<usize> {
+        return self.contains(point).then(|| point.y as usize * self.width + point.x as usize);
+    }
+
+    /// The cell at `point`, or `None` when it lies outside of the grid.
+    pub fn get(self: &Grid<T>, point: Point) -> Option<&T> {
+        return self.index_of(point).map(|index| &self.cells[index]);
+    }
+
+    pub fn get_mut(self: &mut Grid<T>, point: Point) -> Option<&mut T> {
+        return self.index_of(point).map(|index| &mut self.cells[index]);
+    }
+
+    /// Every position of the grid, row by row.
+    pub fn points(self: &Grid<T>) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        return (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x as i32, y as i32)));
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(self: &Grid<T>) -> impl Iterator<Item = (Point, &T)> {
+        return self.points().zip(self.cells.iter());
+    }
+
+    /// The position of the first cell, row by row, matching `predicate`.
+    pub fn position(self: &Grid<T>, predicate: impl Fn(&T) -> bool) -> Option<Point> {
+        return self.iter().find(|(_, cell)| predicate(cell)).map(|(point, _)| point);
+    }
+
+    /// The orthogonal neighbours of `point` that lie on the grid, clockwise starting north.
+    pub fn neighbours_4(self: &Grid<T>, point: Point) -> impl Iterator<Item = Point> + '_ {
+        return point.neighbours_4().into_iter().filter(|neighbour| self.contains(*neighbour));
+    }
+
+    /// The neighbours of `point` including the diagonal ones that lie on the grid, clockwise starting north.
+    pub fn neighbours_8(self: &Grid<T>, point: Point) -> impl Iterator<Item = Point> + '_ {
+        return point.neighbours_8().into_iter().filter(|neighbour| self.contains(*neighbour));
+    }
+
+    pub fn row(self: &Grid<T>, y: usize) -> &[T] {
+        return &self.cells[y * self.width..(y + 1) * self.width];
+    }
+
+    pub fn rows(self: &Grid<T>) -> impl Iterator<Item = &[T]> {
+        return (0..self.height).map(|y| self.row(y));
+    }
+
+    pub fn column(self: &Grid<T>, x: usize) -> impl Iterator<Item = &T> {
+        return self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height);
+    }
+
+    pub fn columns(self: &Grid<T>) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        return (0..self.width).map(|x| self.column(x));
+    }
+
+    pub fn map<U>(self: &Grid<T>, f: impl FnMut(&T) -> U) -> Grid<U> {
+        return Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() };
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    /// Panics when `point` lies outside of the grid, use [`Grid::get`] when that may happen.
+    fn index(&self, point: Point) -> &T {
+        self.get(point).unwrap_or_else(|| panic!("{point:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point).unwrap_or_else(|| panic!("{point:?} is outside of the grid"))
+    }
+}
+
+/// Writes the grid back as text, one line per row.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test_dense {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(input, |c| c.to_digit(10).ok_or("expected a digit"))
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.to_string(), "123\n456");
+        assert_eq!(digits("").unwrap().to_string(), "");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(digits("123\n4x6").unwrap_err(), ParseError::new(2, 2, "x", "expected a digit"));
+        assert_eq!(digits("123\n45").unwrap_err(), ParseError::new(2, 1, "45", "expected a row of 3 cells"));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = digits("12\n34").unwrap();
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        *grid.get_mut(Point::new(1, 1)).unwrap() = 9;
+        assert_eq!(grid.to_string(), "12\n39");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789").unwrap();
+        let corner: Vec<u32> = grid.neighbours_4(Point::new(0, 0)).map(|point| grid[point]).collect();
+        assert_eq!(corner, [2, 4]);
+        assert_eq!(grid.neighbours_8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours_8(Point::new(2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        let columns: Vec<Vec<u32>> = grid.columns().map(|column| column.copied().collect()).collect();
+        assert_eq!(columns, [[1, 4], [2, 5], [3, 6]]);
+        assert_eq!(grid.position(|cell| *cell == 5), Some(Point::new(1, 1)));
+        assert_eq!(grid.map(|cell| cell * 2).row(1), [8, 10, 12]);
+        assert_eq!(Grid::from_fn(2, 1, |point| point.x).row(0), [0, 1]);
+    }
+}
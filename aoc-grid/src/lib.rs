@@ -0,0 +1,10 @@
+//! Two dimensional grids as found in many puzzle inputs: a dense [`Grid`] storing every cell,
+//! and a [`SparseGrid`] storing only the interesting ones.
+
+pub mod dense;
+pub mod point;
+pub mod sparse;
+
+pub use dense::Grid;
+pub use point::Point;
+pub use sparse::SparseGrid;
@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// A position on a grid, or an offset between two positions. `y` grows downwards, the way
+/// puzzle inputs are read, and points are ordered row by row.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const NORTH: Point = Point::new(0, -1);
+    pub const EAST: Point = Point::new(1, 0);
+    pub const SOUTH: Point = Point::new(0, 1);
+    pub const WEST: Point = Point::new(-1, 0);
+
+    /// The four orthogonal directions, clockwise starting north.
+    pub const DIRECTIONS_4: [Point; 4] = [Point::NORTH, Point::EAST, Point::SOUTH, Point::WEST];
+
+    /// All eight directions including the diagonals, clockwise starting north.
+    pub const DIRECTIONS_8: [Point; 8] = [
+        Point::NORTH,
+        Point::new(1, -1),
+        Point::EAST,
+        Point::new(1, 1),
+        Point::SOUTH,
+        Point::new(-1, 1),
+        Point::WEST,
+        Point::new(-1, -1),
+    ];
+
+    pub const fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    pub fn neighbours_4(self: Point) -> [Point; 4] {
+        return Point::DIRECTIONS_4.map(|direction| self + direction);
+    }
+
+    pub fn neighbours_8(self: Point) -> [Point; 8] {
+        return Point::DIRECTIONS_8.map(|direction| self + direction);
+    }
+
+    pub fn manhattan_distance(self: Point, other: Point) -> u32 {
+        return self.x.abs_diff(other.x) + self.y.abs_diff(other.y);
+    }
+}
+
+impl Ord for Point {
+    fn cmp(&self, other: &Point) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i32) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+#[cfg(test)]
+mod test_point {
+    use super::*;
+
+    #[test]
+    fn test_row_major_order() {
+        let mut points = vec![Point::new(0, 1), Point::new(1, 0), Point::new(0, 0)];
+        points.sort();
+        assert_eq!(points, [Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)]);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(Point::new(2, 3) + Point::SOUTH, Point::new(2, 4));
+        assert_eq!(Point::new(2, 3) - Point::new(1, 1), Point::new(1, 2));
+        assert_eq!(-Point::NORTH, Point::SOUTH);
+        assert_eq!(Point::EAST * 3, Point::new(3, 0));
+        assert_eq!(Point::new(1, 1).manhattan_distance(Point::new(-2, 3)), 5);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(Point::new(0, 0).neighbours_4(), [Point::new(0, -1), Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0)]);
+        assert!(Point::new(0, 0).neighbours_8().contains(&Point::new(-1, -1)));
+    }
+}
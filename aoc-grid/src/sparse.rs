@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use aoc_common::ParseError;
+
+use crate::Point;
+
+/// A grid storing only the cells that hold something, for inputs that are mostly empty.
+/// The grid spans from the origin to the furthest cell, or the extent of the parsed text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    width: usize,
+    height: usize,
+    cells: BTreeMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Parses every character of `input` with `cell`, which returns `None` for empty cells.
+    /// A cell that fails to parse is reported at its position in `input`.
+    pub fn parse<E: Into<String>>(input: &str, mut cell: impl FnMut(char) -> Result<Option<T>, E>) -> Result<SparseGrid<T>, ParseError> {
+        let mut grid = SparseGrid { width: 0, height: input.lines().count(), cells: BTreeMap::new() };
+        for (y, line) in input.lines().enumerate() {
+            grid.width = grid.width.max(line.chars().count());
+            for (x, (index, c)) in line.char_indices().enumerate() {
+                let parsed = cell(c).map_err(|message| ParseError::locate(input, &line[index..index + c.len_utf8()], message))?;
+                if let Some(parsed) = parsed {
+                    grid.cells.insert(Point::new(x as i32, y as i32), parsed);
+                }
+            }
+        }
+        return Ok(grid);
+    }
+
+    pub fn width(self: &SparseGrid<T>) -> usize {
+        self.width
+    }
+
+    pub fn height(self: &SparseGrid<T>) -> usize {
+        self.height
+    }
+
+    /// Whether `point` lies within the grid, regardless of the cell there being empty.
+    pub fn contains(self: &SparseGrid<T>, point: Point) -> bool {
+        return point.x >= 0 && point.y >= 0 && (point.x as usize) < self.width && (point.y as usize) < self.height;
+    }
+
+    pub fn get(self: &SparseGrid<T>, point: Point) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    /// Fills the cell at `point`, growing the grid when it lies beyond it. Panics on negative coordinates.
+    pub fn insert(self: &mut SparseGrid<T>, point: Point, cell: T) -> Option<T> {
+        assert!(point.x >= 0 && point.y >= 0, "{point:?} is outside of the grid");
+        self.width = self.width.max(point.x as usize + 1);
+        self.height = self.height.max(point.y as usize + 1);
+        return self.cells.insert(point, cell);
+    }
+
+    pub fn len(self: &SparseGrid<T>) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(self: &SparseGrid<T>) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The filled cells with their positions, row by row.
+    pub fn iter(self: &SparseGrid<T>) -> impl Iterator<Item = (Point, &T)> {
+        return self.cells.iter().map(|(point, cell)| (*point, cell));
+    }
+
+    /// The filled orthogonal neighbours of `point`, clockwise starting north.
+    pub fn neighbours_4(self: &SparseGrid<T>, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        return point.neighbours_4().into_iter().filter_map(|neighbour| Some((neighbour, self.get(neighbour)?)));
+    }
+
+    /// The filled neighbours of `point` including the diagonal ones, clockwise starting north.
+    pub fn neighbours_8(self: &SparseGrid<T>, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        return point.neighbours_8().into_iter().filter_map(|neighbour| Some((neighbour, self.get(neighbour)?)));
+    }
+
+    /// The filled cells of row `y`, left to right.
+    pub fn row(self: &SparseGrid<T>, y: usize) -> impl Iterator<Item = (Point, &T)> {
+        let y = y as i32;
+        return self.cells.range(Point::new(0, y)..Point::new(0, y + 1)).map(|(point, cell)| (*point, cell));
+    }
+
+    /// The filled cells of column `x`, top to bottom.
+    pub fn column(self: &SparseGrid<T>, x: usize) -> impl Iterator<Item = (Point, &T)> {
+        return self.iter().filter(move |(point, _)| point.x == x as i32);
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(cells: I) -> SparseGrid<T> {
+        let mut grid = SparseGrid { width: 0, height: 0, cells: BTreeMap::new() };
+        for (point, cell) in cells {
+            grid.insert(point, cell);
+        }
+        return grid;
+    }
+}
+
+/// Writes the grid back as text, one line per row and a `.` for every empty cell.
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                match self.get(Point::new(x as i32, y as i32)) {
+                    Some(cell) => write!(f, "{cell}")?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test_sparse {
+    use super::*;
+
+    fn symbols(input: &str) -> Result<SparseGrid<char>, ParseError> {
+        SparseGrid::parse(input, |c| match c {
+            '.' => Ok(None),
+            ' ' => Err("unexpected space"),
+            c => Ok(Some(c)),
+        })
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let grid = symbols("#..\n.*.\n..#").unwrap();
+        assert_eq!((grid.width(), grid.height(), grid.len()), (3, 3, 3));
+        assert_eq!(grid.get(Point::new(1, 1)), Some(&'*'));
+        assert_eq!(grid.get(Point::new(1, 0)), None);
+        assert_eq!(grid.to_string(), "#..\n.*.\n..#");
+        assert_eq!(symbols("..\n. ").unwrap_err(), ParseError::new(2, 2, " ", "unexpected space"));
+    }
+
+    #[test]
+    fn test_iteration_is_row_by_row() {
+        let grid = symbols("..a\nb..\n.c.").unwrap();
+        assert_eq!(grid.iter().map(|(_, c)| *c).collect::<String>(), "abc");
+        assert_eq!(grid.row(1).map(|(point, _)| point).collect::<Vec<_>>(), [Point::new(0, 1)]);
+        assert_eq!(grid.column(1).map(|(_, c)| *c).collect::<String>(), "c");
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = symbols("ab.\n.c.\nd.e").unwrap();
+        assert_eq!(grid.neighbours_4(Point::new(1, 1)).map(|(_, c)| *c).collect::<String>(), "b");
+        assert_eq!(grid.neighbours_8(Point::new(1, 1)).map(|(_, c)| *c).collect::<String>(), "beda");
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let grid: SparseGrid<char> = [(Point::new(2, 1), 'x'), (Point::new(0, 0), 'y')].into_iter().collect();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid.contains(Point::new(1, 1)));
+        assert!(!grid.contains(Point::new(3, 0)));
+        assert_eq!(grid.to_string(), "y..\n..x");
+    }
+}
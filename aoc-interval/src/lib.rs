@@ -0,0 +1,8 @@
+//! Half-open ranges of values: a [`RangeSet`] of disjoint ranges with the usual set operations, and a
+//! [`RangeMap`] shifting ranges piece by piece, for puzzles too large to handle value by value.
+
+pub mod map;
+pub mod set;
+
+pub use map::{Piece, RangeMap};
+pub use set::RangeSet;
@@ -0,0 +1,177 @@
+use std::ops::{Add, Range, Sub};
+
+use crate::RangeSet;
+
+/// Values in `source` are mapped to the same offset from `destination`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Piece<T> {
+    pub source: Range<T>,
+    pub destination: T,
+}
+
+/// A piecewise linear map: values within one of its pieces are shifted to that piece's destination,
+/// all other values are mapped to themselves.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RangeMap<T> {
+    /// Sorted by their source, which never overlap.
+    pieces: Vec<Piece<T>>,
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> RangeMap<T> {
+    /// The identity map.
+    pub fn new() -> RangeMap<T> {
+        RangeMap { pieces: vec![] }
+    }
+
+    /// Maps `source` onto the range starting at `destination`. Returns `false`, leaving the map as it
+    /// was, when `source` overlaps a range that is already mapped.
+    pub fn insert(self: &mut RangeMap<T>, source: Range<T>, destination: T) -> bool {
+        if source.is_empty() {
+            return true;
+        }
+        let index = self.pieces.partition_point(|piece| piece.source.end <= source.start);
+        if self.pieces.get(index).is_some_and(|piece| piece.source.start < source.end) {
+            return false;
+        }
+        self.pieces.insert(index, Piece { source, destination });
+        return true;
+    }
+
+    pub fn pieces(self: &RangeMap<T>) -> &[Piece<T>] {
+        &self.pieces
+    }
+
+    pub fn get(self: &RangeMap<T>, value: T) -> T {
+        let index = self.pieces.partition_point(|piece| piece.source.end <= value);
+        return match self.pieces.get(index) {
+            Some(piece) if piece.source.start <= value => value - piece.source.start + piece.destination,
+            _ => value,
+        };
+    }
+
+    /// Splits `range` into the parts that are mapped alike, each with the value its start maps to.
+    fn segments(self: &RangeMap<T>, range: Range<T>) -> Vec<(Range<T>, T)> {
+        let mut segments = vec![];
+        if range.is_empty() {
+            return segments;
+        }
+        let mut start = range.start;
+        let first = self.pieces.partition_point(|piece| piece.source.end <= range.start);
+        for piece in self.pieces[first..].iter().take_while(|piece| piece.source.start < range.end) {
+            if start < piece.source.start {
+                segments.push((start..piece.source.start, start));
+                start = piece.source.start;
+            }
+            let end = piece.source.end.min(range.end);
+            segments.push((start..end, start - piece.source.start + piece.destination));
+            start = end;
+        }
+        if start < range.end {
+            segments.push((start..range.end, start));
+        }
+        return segments;
+    }
+
+    /// The values `range` maps to, one range for every part of it that is mapped alike.
+    /// Parts mapped by different pieces may well end up overlapping.
+    pub fn map_range(self: &RangeMap<T>, range: Range<T>) -> Vec<Range<T>> {
+        return self.segments(range).into_iter()
+            .map(|(segment, start)| start..start + (segment.end - segment.start))
+            .collect();
+    }
+
+    pub fn map_set(self: &RangeMap<T>, set: &RangeSet<T>) -> RangeSet<T> {
+        return set.ranges().iter().flat_map(|range| self.map_range(range.clone())).collect();
+    }
+
+    /// The map applying `self` first and `next` to the result, as a single map.
+    pub fn compose(self: &RangeMap<T>, next: &RangeMap<T>) -> RangeMap<T> {
+        let mut composed = RangeMap::new();
+        for piece in &self.pieces {
+            let image = piece.destination..piece.destination + (piece.source.end - piece.source.start);
+            for (segment, destination) in next.segments(image) {
+                let start = segment.start - piece.destination + piece.source.start;
+                composed.insert_shift(start..start + (segment.end - segment.start), destination);
+            }
+        }
+        // Where `self` is the identity, the composed map is simply `next`.
+        let mapped: RangeSet<T> = self.pieces.iter().map(|piece| piece.source.clone()).collect();
+        for piece in &next.pieces {
+            let unmapped = RangeSet::from_iter([piece.source.clone()]).difference(&mapped);
+            for range in unmapped.ranges() {
+                composed.insert_shift(range.clone(), range.start - piece.source.start + piece.destination);
+            }
+        }
+        return composed;
+    }
+
+    /// Inserts a piece known not to overlap, leaving out the ones that map values to themselves.
+    fn insert_shift(self: &mut RangeMap<T>, source: Range<T>, destination: T) {
+        if source.start != destination {
+            let inserted = self.insert(source, destination);
+            debug_assert!(inserted);
+        }
+    }
+}
+
+impl<T: Copy + Ord + Add<Output = T> + Sub<Output = T>> Default for RangeMap<T> {
+    fn default() -> RangeMap<T> {
+        RangeMap::new()
+    }
+}
+
+#[cfg(test)]
+mod test_map {
+    use super::*;
+
+    fn map(pieces: &[(Range<u32>, u32)]) -> RangeMap<u32> {
+        let mut map = RangeMap::new();
+        for (source, destination) in pieces {
+            assert!(map.insert(source.clone(), *destination));
+        }
+        return map;
+    }
+
+    /// A few maps over values below 20, with pieces that swap, shift up and shift down.
+    fn maps() -> Vec<RangeMap<u32>> {
+        return vec![
+            RangeMap::new(),
+            map(&[(2..5, 10)]),
+            map(&[(0..3, 3), (3..6, 0)]),
+            map(&[(8..12, 1), (14..16, 17)]),
+            map(&[(1..2, 9), (4..9, 12), (12..15, 0)]),
+        ];
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut map = map(&[(5..8, 20), (0..2, 10)]);
+        assert!(!map.insert(7..9, 0));
+        assert!(!map.insert(0..20, 0));
+        assert!(map.insert(8..9, 0));
+        let mapped: Vec<u32> = (0..10).map(|value| map.get(value)).collect();
+        assert_eq!(mapped, [10, 11, 2, 3, 4, 20, 21, 22, 0, 9]);
+    }
+
+    #[test]
+    fn test_map_range() {
+        let map = map(&[(2..5, 10), (6..8, 0)]);
+        assert_eq!(map.map_range(0..7), [0..2, 10..13, 5..6, 0..1]);
+        assert_eq!(map.map_range(3..3), []);
+        let set = RangeSet::from_iter([0..3, 3..7]);
+        assert_eq!(map.map_set(&set).ranges(), [0..2, 5..6, 10..13]);
+    }
+
+    #[test]
+    fn test_compose_against_values() {
+        for first in maps() {
+            for second in maps() {
+                let composed = first.compose(&second);
+                for value in 0..20 {
+                    assert_eq!(composed.get(value), second.get(first.get(value)), "{value} through {first:?} and {second:?}");
+                }
+                assert!(composed.pieces().iter().all(|piece| piece.source.start != piece.destination));
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::ops::Range;
+
+/// A set of values stored as sorted, disjoint ranges. Ranges that overlap or touch are merged, so sets
+/// holding the same values are equal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet { ranges: vec![] }
+    }
+
+    /// Adds the values of `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(self: &mut RangeSet<T>, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|other| other.end < range.start);
+        let last = self.ranges.partition_point(|other| other.start <= range.end);
+        let mut merged = range;
+        if first < last {
+            merged.start = merged.start.min(self.ranges[first].start);
+            merged.end = merged.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// The ranges of the set, in increasing order.
+    pub fn ranges(self: &RangeSet<T>) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(self: &RangeSet<T>) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(self: &RangeSet<T>, value: T) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= value);
+        return self.ranges.get(index).is_some_and(|range| range.start <= value);
+    }
+
+    /// The smallest value in the set.
+    pub fn min(self: &RangeSet<T>) -> Option<T> {
+        return self.ranges.first().map(|range| range.start);
+    }
+
+    pub fn union(self: &RangeSet<T>, other: &RangeSet<T>) -> RangeSet<T> {
+        return self.ranges.iter().chain(&other.ranges).cloned().collect();
+    }
+
+    pub fn intersection(self: &RangeSet<T>, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = RangeSet::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            result.insert(a.start.max(b.start)..a.end.min(b.end));
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+        return result;
+    }
+
+    /// The values of `self` that are not in `other`.
+    pub fn difference(self: &RangeSet<T>, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = RangeSet::new();
+        for range in &self.ranges {
+            let mut start = range.start;
+            let first = other.ranges.partition_point(|other| other.end <= range.start);
+            for removed in other.ranges[first..].iter().take_while(|other| other.start < range.end) {
+                result.insert(start..removed.start);
+                start = start.max(removed.end);
+            }
+            result.insert(start..range.end);
+        }
+        return result;
+    }
+}
+
+impl<T: Copy + Ord> Default for RangeSet<T> {
+    fn default() -> RangeSet<T> {
+        RangeSet::new()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> RangeSet<T> {
+        let mut set = RangeSet::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        return set;
+    }
+}
+
+#[cfg(test)]
+mod test_set {
+    use super::*;
+
+    /// Sets of values below 12 for every combination of ranges with one, two or no gaps.
+    fn sets() -> Vec<RangeSet<u32>> {
+        let mut sets = vec![];
+        for a in 0..12 {
+            for b in a..12 {
+                for c in b..12 {
+                    sets.push(RangeSet::from_iter([a..b, c..12]));
+                    sets.push(RangeSet::from_iter([0..a, b..c]));
+                }
+            }
+        }
+        return sets;
+    }
+
+    fn values(set: &RangeSet<u32>) -> Vec<u32> {
+        return (0..12).filter(|value| set.contains(*value)).collect();
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let set = RangeSet::from_iter([5..7, 0..2, 2..3, 6..9, 4..4]);
+        assert_eq!(set.ranges(), [0..3, 5..9]);
+        assert_eq!(set.min(), Some(0));
+        assert!(set.contains(8));
+        assert!(!set.contains(3));
+        assert!(!set.contains(9));
+        assert!(RangeSet::<u32>::new().is_empty());
+    }
+
+    #[test]
+    fn test_operations_against_values() {
+        let sets = sets();
+        for a in &sets {
+            for b in sets.iter().step_by(13) {
+                let (a_values, b_values) = (values(a), values(b));
+                let union: Vec<u32> = (0..12).filter(|v| a_values.contains(v) || b_values.contains(v)).collect();
+                let intersection: Vec<u32> = a_values.iter().copied().filter(|v| b_values.contains(v)).collect();
+                let difference: Vec<u32> = a_values.iter().copied().filter(|v| !b_values.contains(v)).collect();
+                assert_eq!(values(&a.union(b)), union, "{a:?} | {b:?}");
+                assert_eq!(values(&a.intersection(b)), intersection, "{a:?} & {b:?}");
+                assert_eq!(values(&a.difference(b)), difference, "{a:?} - {b:?}");
+                // The results are normalised as well, so equal values make equal sets.
+                assert_eq!(a.intersection(b), b.intersection(a));
+                assert_eq!(a.union(b), b.union(a));
+            }
+        }
+    }
+}
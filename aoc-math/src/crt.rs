@@ -0,0 +1,85 @@
+use crate::gcd::extended_gcd;
+
+/// `x ≡ residue (mod modulus)`, with a positive modulus.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    pub fn new(residue: i128, modulus: i128) -> Congruence {
+        assert!(modulus > 0, "the modulus of a congruence must be positive, not {modulus}");
+        return Congruence { residue: residue.rem_euclid(modulus), modulus };
+    }
+
+    /// The congruence satisfied by exactly the numbers satisfying both, if there are any.
+    /// The moduli do not need to be coprime. `None` as well when the combined modulus overflows.
+    pub fn combine(self: Congruence, other: Congruence) -> Option<Congruence> {
+        let (g, x, _) = extended_gcd(self.modulus, other.modulus);
+        let difference = other.residue - self.residue;
+        if difference % g != 0 {
+            return None;
+        }
+        let modulus = (self.modulus / g).checked_mul(other.modulus)?;
+        // self.modulus * x ≡ g (mod other.modulus), so stepping k times self.modulus covers the difference.
+        let steps = (difference / g).checked_mul(x)?.rem_euclid(other.modulus / g);
+        let residue = self.modulus.checked_mul(steps)?.checked_add(self.residue)?;
+        return Some(Congruence::new(residue, modulus));
+    }
+}
+
+/// The numbers satisfying all `congruences` at once, as a single congruence, or `None` when no number
+/// does or the combined modulus overflows. No congruences at all are satisfied by every number.
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    return congruences.into_iter().try_fold(Congruence::new(0, 1), Congruence::combine);
+}
+
+#[cfg(test)]
+mod test_crt {
+    use super::*;
+
+    /// The smallest non-negative number satisfying all congruences, by trying them one by one.
+    fn oracle(congruences: &[Congruence]) -> Option<i128> {
+        let limit: i128 = congruences.iter().map(|c| c.modulus).product();
+        return (0..limit).find(|x| congruences.iter().all(|c| x.rem_euclid(c.modulus) == c.residue));
+    }
+
+    #[test]
+    fn test_coprime() {
+        let congruences = [Congruence::new(2, 3), Congruence::new(3, 5), Congruence::new(2, 7)];
+        assert_eq!(crt(congruences), Some(Congruence::new(23, 105)));
+    }
+
+    #[test]
+    fn test_not_coprime() {
+        assert_eq!(crt([Congruence::new(2, 4), Congruence::new(4, 6)]), Some(Congruence::new(10, 12)));
+        assert_eq!(crt([Congruence::new(1, 4), Congruence::new(2, 6)]), None);
+        assert_eq!(crt([]), Some(Congruence::new(0, 1)));
+    }
+
+    #[test]
+    fn test_against_oracle() {
+        for m1 in 1..10 {
+            for m2 in 1..10 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let congruences = [Congruence::new(r1, m1), Congruence::new(r2, m2)];
+                        let solved = crt(congruences);
+                        assert_eq!(solved.map(|c| c.residue), oracle(&congruences), "{congruences:?}");
+                        if let Some(solved) = solved {
+                            assert_eq!(solved.modulus, m1 * m2 / extended_gcd(m1, m2).0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let huge = i128::MAX / 2;
+        assert_eq!(crt([Congruence::new(0, huge), Congruence::new(0, huge - 2)]), None);
+        assert_eq!(Congruence::new(-1, 5), Congruence::new(4, 5));
+    }
+}
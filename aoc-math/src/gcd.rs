@@ -0,0 +1,73 @@
+pub fn gcd(a: u128, b: u128) -> u128 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    return a;
+}
+
+/// The least common multiple of `a` and `b`, or `None` when it does not fit in a `u128`.
+/// Divides before multiplying, so only results that are too large themselves overflow.
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    return (a / gcd(a, b)).checked_mul(b);
+}
+
+/// The least common multiple of all `numbers`, which is 1 for none at all.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u128>) -> Option<u128> {
+    return numbers.into_iter().try_fold(1, lcm);
+}
+
+/// Returns `(g, x, y)` with `g` the greatest common divisor of `a` and `b`, never negative,
+/// and `a * x + b * y == g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    return (old_r, old_x, old_y);
+}
+
+#[cfg(test)]
+mod test_gcd {
+    use super::*;
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm_all([2, 3, 4]), Some(12));
+        assert_eq!(lcm_all([]), Some(1));
+    }
+
+    #[test]
+    fn test_lcm_overflow() {
+        // The product overflows a `u64` long before the result does.
+        let big = u64::MAX as u128;
+        assert_eq!(lcm(big, big), Some(big));
+        assert_eq!(lcm(u128::MAX, u128::MAX - 1), None);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for a in -30..30 {
+            for b in -30..30 {
+                let (g, x, y) = extended_gcd(a, b);
+                assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i128, "gcd of {a} and {b}");
+                assert_eq!(a * x + b * y, g, "coefficients for {a} and {b}");
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+//! Number theory for puzzles built on repeating cycles: greatest common divisors, least common
+//! multiples that report overflow instead of wrapping, and Chinese remainder solving.
+
+pub mod crt;
+pub mod gcd;
+
+pub use crt::{crt, Congruence};
+pub use gcd::{extended_gcd, gcd, lcm, lcm_all};
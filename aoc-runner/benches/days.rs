@@ -0,0 +1,52 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use aoc_common::Solution;
+use aoc_runner::answers::Answers;
+use aoc_runner::{days, Part};
+
+/// Benchmarks parsing and each part of a day separately, on every input `answers.toml` knows about
+/// that is present locally. Like `aoc verify`, examples only get the parts they have an answer for.
+fn bench_day<S: Solution>(c: &mut Criterion) {
+    let day = days::find(S::DAY).expect("day is registered");
+    let inputs = day.inputs();
+    let answers = Answers::read(&inputs).unwrap_or_else(|err| panic!("{err}"));
+    let mut group = c.benchmark_group(day.crate_name());
+    for input_name in answers.input_names() {
+        let Ok(input) = inputs.read(&format!("{input_name}.txt")) else {
+            continue;
+        };
+        let parsed = match S::parse(&input) {
+            Ok(parsed) => parsed,
+            Err(err) => panic!("{} {input_name}: {err}", day.crate_name()),
+        };
+        let params = S::params_from_env().unwrap_or_else(|err| panic!("{err}"));
+        group.bench_function(format!("parse/{input_name}"), |b| b.iter(|| S::parse(black_box(&input))));
+        for part in answers.parts(input_name) {
+            let id = format!("part{}/{input_name}", part.number());
+            match part {
+                Part::One => group.bench_function(id, |b| b.iter(|| S::part1(black_box(&parsed), &params))),
+                Part::Two => group.bench_function(id, |b| b.iter(|| S::part2(black_box(&parsed), &params))),
+            };
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_day::<day01::Day01>,
+    bench_day::<day02::Day02>,
+    bench_day::<day03::Day03>,
+    bench_day::<day04::Day04>,
+    bench_day::<day05::Day05>,
+    bench_day::<day06::Day06>,
+    bench_day::<day07::Day07>,
+    bench_day::<day08::Day08>,
+    bench_day::<day09::Day09>,
+    bench_day::<day10::Day10>,
+    bench_day::<day11::Day11>,
+    bench_day::<day12::Day12>,
+);
+criterion_main!(benches);
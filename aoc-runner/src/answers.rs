@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use serde::Deserialize;
+
+use aoc_common::{InputError, Inputs};
+
+use crate::days::{Day, Part, SolvedPart};
+use crate::measure::Measurement;
+use crate::report;
+
+/// Name of the file, next to the inputs of a day, recording the accepted answers.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// The accepted answers of a single day, keyed by the name of the input file
+/// (without `.txt`) they belong to:
+///
+/// ```toml
+/// [input]
+/// part1 = 2164
+/// part2 = "1632"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    inputs: BTreeMap<String, ExpectedAnswers>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExpectedAnswers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+/// An answer as written down in the answers file, either as a number or as text.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+impl Answers {
+    /// Reads the answers file from `inputs`, which is allowed to be missing.
+    pub fn read(inputs: &Inputs) -> Result<Answers, String> {
+        return match inputs.read(ANSWERS_FILE) {
+            Ok(content) => Answers::parse(&content)
+                .map_err(|err| format!("invalid {}: {err}", inputs.path(ANSWERS_FILE).display())),
+            Err(InputError::Missing { .. }) => Ok(Answers::default()),
+            Err(err) => Err(err.to_string()),
+        };
+    }
+
+    pub fn parse(content: &str) -> Result<Answers, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// The input files answers are recorded for, `input` always being one of them.
+    pub fn input_names(self: &Answers) -> Vec<&str> {
+        let mut names: Vec<&str> = self.inputs.keys().map(String::as_str).collect();
+        if !self.inputs.contains_key("input") {
+            names.insert(0, "input");
+        }
+        return names;
+    }
+
+    /// The parts worth solving for an input: both for the real input, only those with an answer for examples,
+    /// as a part of an example for the other part may not even have a solution.
+    pub fn parts(self: &Answers, input_name: &str) -> Vec<Part> {
+        return [Part::One, Part::Two].into_iter()
+            .filter(|part| input_name == "input" || self.expected(input_name, *part).is_some())
+            .collect();
+    }
+
+    pub fn expected(self: &Answers, input_name: &str, part: Part) -> Option<&Answer> {
+        let expected = self.inputs.get(input_name)?;
+        return match part {
+            Part::One => expected.part1.as_ref(),
+            Part::Two => expected.part2.as_ref(),
+        };
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: String, actual: String },
+    /// The part was solved, but no accepted answer is known for it.
+    MissingAnswer { actual: String },
+    MissingInput,
+    Error(String),
+}
+
+impl Status {
+    /// Short, stable name of the status for machine-readable output.
+    pub fn name(self: &Status) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail { .. } => "fail",
+            Status::MissingAnswer { .. } => "missing-answer",
+            Status::MissingInput => "missing-input",
+            Status::Error(_) => "error",
+        }
+    }
+
+    pub fn is_failure(self: &Status) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => write!(f, "FAIL (expected {expected}, got {actual})"),
+            Status::MissingAnswer { actual } => write!(f, "missing answer (got {actual})"),
+            Status::MissingInput => write!(f, "missing input"),
+            Status::Error(message) => write!(f, "ERROR ({message})"),
+        }
+    }
+}
+
+/// The outcome of checking one part of a day against one of its inputs.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub day: u8,
+    pub input_name: String,
+    pub part: Part,
+    pub status: Status,
+    /// Only known when the input could be read.
+    pub input_hash: Option<String>,
+    /// Only known when the input could be parsed, as are the answer and its measurement.
+    pub parse: Option<Measurement>,
+    pub solved: Option<SolvedPart>,
+}
+
+/// Solves every input of `day` answers are recorded for, and compares the results with
+/// those answers. The real input is always checked, examples only for the parts they have answers for.
+/// Answers are read from `inputs`, the inputs themselves by name with `read_input`.
+pub fn verify(day: &Day, inputs: &Inputs, read_input: impl Fn(&str) -> Result<String, InputError>) -> Vec<Check> {
+    let answers = match Answers::read(inputs) {
+        Ok(answers) => answers,
+        Err(message) => {
+            return vec![Check::unsolved(day, ANSWERS_FILE, Part::One, Status::Error(message), None)];
+        }
+    };
+    return answers.input_names().into_iter()
+        .flat_map(|input_name| {
+            let input = read_input(input_name);
+            check(day, input_name, input, &answers, &answers.parts(input_name))
+        })
+        .collect();
+}
+
+/// Solves `parts` of `day` on an input, which may have failed to be read, and compares the results
+/// with the answers recorded for `input_name`. Each part is solved on its own, so one failing leaves the
+/// others checked.
+pub fn check(day: &Day, input_name: &str, input: Result<String, InputError>, answers: &Answers, parts: &[Part]) -> Vec<Check> {
+    let unsolved = |status: Status, input_hash: Option<String>| -> Vec<Check> {
+        return parts.iter()
+            .map(|part| Check::unsolved(day, input_name, *part, status.clone(), input_hash.clone()))
+            .collect();
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(InputError::Missing { .. }) => return unsolved(Status::MissingInput, None),
+        Err(err) => return unsolved(Status::Error(err.to_string()), None),
+    };
+    let input_hash = report::input_hash(&input);
+    return parts.iter()
+        .flat_map(|part| match day.solve(&input, &[*part]) {
+            Ok(solved) => solved.parts.into_iter()
+                .map(|solved_part| Check {
+                    day: day.number,
+                    input_name: input_name.to_string(),
+                    part: solved_part.part,
+                    status: compare(answers.expected(input_name, solved_part.part), solved_part.answer.clone()),
+                    input_hash: Some(input_hash.clone()),
+                    parse: Some(solved.parse),
+                    solved: Some(solved_part),
+                })
+                .collect(),
+            Err(err) => vec![Check::unsolved(day, input_name, *part, Status::Error(err.to_string()), Some(input_hash.clone()))],
+        })
+        .collect();
+}
+
+impl Check {
+    fn unsolved(day: &Day, input_name: &str, part: Part, status: Status, input_hash: Option<String>) -> Check {
+        return Check { day: day.number, input_name: input_name.to_string(), part, status, input_hash, parse: None, solved: None };
+    }
+}
+
+fn compare(expected: Option<&Answer>, actual: String) -> Status {
+    return match expected {
+        Some(expected) if expected.to_string() == actual => Status::Pass,
+        Some(expected) => Status::Fail { expected: expected.to_string(), actual },
+        None => Status::MissingAnswer { actual },
+    };
+}
+
+#[cfg(test)]
+mod test_answers {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("[input]\npart1 = 142\npart2 = \"281\"\n\n[example2]\npart2 = 281").unwrap();
+        assert_eq!(answers.input_names(), vec!["example2", "input"]);
+        assert_eq!(answers.expected("input", Part::One), Some(&Answer::Number(142)));
+        assert_eq!(answers.expected("input", Part::Two).map(|answer| answer.to_string()), Some("281".to_string()));
+        assert_eq!(answers.expected("example2", Part::One), None);
+        assert!(Answers::parse("[input]\npart3 = 1").is_err());
+    }
+
+    #[test]
+    fn test_input_is_always_checked() {
+        assert_eq!(Answers::parse("").unwrap().input_names(), vec!["input"]);
+    }
+
+    #[test]
+    fn test_parts() {
+        let answers = Answers::parse("[example]\npart2 = 4\n").unwrap();
+        assert_eq!(answers.parts("example"), vec![Part::Two]);
+        assert_eq!(answers.parts("input"), vec![Part::One, Part::Two]);
+    }
+
+    #[test]
+    fn test_check_parts_on_their_own() {
+        let day05 = crate::days::find(5).unwrap();
+        let maps = ["seed-to-soil", "soil-to-fertilizer", "fertilizer-to-water", "water-to-light", "light-to-temperature",
+            "temperature-to-humidity", "humidity-to-location"];
+        let almanac = maps.iter().fold("seeds: 3 4 20".to_string(), |almanac, map| almanac + &format!("\n\n{map} map:\n10 0 5"));
+        let answers = Answers::parse("[input]\npart1 = 13").unwrap();
+        let checks = check(day05, "input", Ok(almanac), &answers, &[Part::One, Part::Two]);
+        assert_eq!(checks.iter().map(|check| check.part).collect::<Vec<_>>(), [Part::One, Part::Two]);
+        assert_eq!(checks[0].status, Status::Pass);
+        assert_eq!(checks[1].status, Status::Error("no answer: the seeds do not come in pairs of a start and a length".to_string()));
+        assert!(checks[1].input_hash.is_some());
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare(Some(&Answer::Number(8)), "8".to_string()), Status::Pass);
+        assert!(compare(Some(&Answer::Number(8)), "9".to_string()).is_failure());
+        assert_eq!(compare(None, "9".to_string()), Status::MissingAnswer { actual: "9".to_string() });
+    }
+}
@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+
+use aoc_common::{Inputs, Solution, SolveError};
+
+use crate::measure::{measure, Measurement};
+
+/// A single puzzle day as seen by the runner, with its `Solution` erased
+/// so all days can be kept in one table.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    solve: fn(&str, &[Part]) -> Result<Solved, SolveError>,
+    diagnose: Option<Diagnose>,
+}
+
+/// Explains the answer of a part line by line, with an error for every line that spoils it. Fails when
+/// the day cannot be set up to explain itself.
+pub type Diagnose = fn(&str, Part) -> Result<Vec<Result<String, String>>, String>;
+
+/// The answers of a day on a single input, with how long parsing and each part took.
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub parse: Measurement,
+    pub parts: Vec<SolvedPart>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SolvedPart {
+    pub part: Part,
+    pub answer: String,
+    pub measurement: Measurement,
+}
+
+impl Solved {
+    pub fn answers(self: &Solved) -> Vec<String> {
+        return self.parts.iter().map(|part| part.answer.clone()).collect();
+    }
+
+    /// Parsing and all parts together.
+    pub fn total(self: &Solved) -> Measurement {
+        let parts = self.parts.iter().map(|part| &part.measurement);
+        return Measurement::total(std::iter::once(&self.parse).chain(parts));
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Part { One, Two }
+
+impl Part {
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(self: &Part) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Day {
+    pub const fn of<S: Solution>() -> Day {
+        Day { number: S::DAY, solve: solve::<S>, diagnose: None }
+    }
+
+    pub const fn with_diagnostics(self: Day, diagnose: Diagnose) -> Day {
+        Day { diagnose: Some(diagnose), ..self }
+    }
+
+    pub fn has_diagnostics(self: &Day) -> bool {
+        self.diagnose.is_some()
+    }
+
+    /// The explanation of `part` on `input` line by line, `None` for days that cannot explain themselves.
+    pub fn diagnose(self: &Day, input: &str, part: Part) -> Option<Result<Vec<Result<String, String>>, String>> {
+        self.diagnose.map(|diagnose| diagnose(input, part))
+    }
+
+    /// Parses `input` once and renders the answer of each of the requested parts, failing when any of
+    /// them has no answer.
+    pub fn solve(self: &Day, input: &str, parts: &[Part]) -> Result<Solved, SolveError> {
+        (self.solve)(input, parts)
+    }
+
+    pub fn crate_name(self: &Day) -> String {
+        format!("day{:02}", self.number)
+    }
+
+    /// Where the day's own binary would look for its inputs.
+    pub fn inputs(self: &Day) -> Inputs {
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..").join(self.crate_name());
+        Inputs::for_crate(&self.crate_name(), crate_dir)
+    }
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Solved, SolveError> {
+    let _day = S::span().entered();
+    let params = S::params_from_env().map_err(SolveError::Params)?;
+    let (parsed, parse) = measure(|| S::parse_traced(input));
+    let parsed = parsed?;
+    let parts = parts.iter()
+        .map(|part| {
+            let (answer, measurement) = match part {
+                Part::One => measure(|| S::part1_traced(&parsed, &params)),
+                Part::Two => measure(|| S::part2_traced(&parsed, &params)),
+            };
+            return Ok(SolvedPart { part: *part, answer: answer?.to_string(), measurement });
+        })
+        .collect::<Result<_, SolveError>>()?;
+    return Ok(Solved { parse, parts });
+}
+
+/// Part 1 only reads the digits themselves, part 2 the vocabulary the day is solved with.
+fn diagnose_day01(input: &str, part: Part) -> Result<Vec<Result<String, String>>, String> {
+    let vocabulary = match part {
+        Part::One => day01::Vocabulary::digits(),
+        Part::Two => day01::Day01::params_from_env()?,
+    };
+    return Ok(day01::diagnose(input, &vocabulary).iter()
+        .map(|diagnosis| match diagnosis.calibration {
+            Ok(_) => Ok(diagnosis.to_string()),
+            Err(_) => Err(diagnosis.to_string()),
+        })
+        .collect());
+}
+
+/// Every day the runner knows about, in order.
+pub const DAYS: &[Day] = &[
+    Day::of::<day01::Day01>().with_diagnostics(diagnose_day01),
+    Day::of::<day02::Day02>(),
+    Day::of::<day03::Day03>(),
+    Day::of::<day04::Day04>(),
+    Day::of::<day05::Day05>(),
+    Day::of::<day06::Day06>(),
+    Day::of::<day07::Day07>(),
+    Day::of::<day08::Day08>(),
+    Day::of::<day09::Day09>(),
+    Day::of::<day10::Day10>(),
+    Day::of::<day11::Day11>(),
+    Day::of::<day12::Day12>(),
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod test_days {
+    use super::*;
+
+    #[test]
+    fn test_days_are_unique_and_ordered() {
+        assert!(DAYS.windows(2).all(|pair| pair[0].number < pair[1].number));
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let day01 = find(1).unwrap();
+        let diagnosis = day01.diagnose("a1b\nseven", Part::One).unwrap().unwrap();
+        assert_eq!(diagnosis[0], Ok("line 1: `a1b`: first `1` at 1..2, last `1` at 1..2, value 11".to_string()));
+        assert_eq!(diagnosis[1], Err("line 2: no answer: there is no digit in `seven`".to_string()));
+        assert_eq!(day01.diagnose("a1b\nseven", Part::Two).unwrap().unwrap()[1].as_ref().unwrap(), "line 2: `seven`: first `seven` at 0..5, last `seven` at 0..5, value 77");
+        assert!(find(2).unwrap().diagnose("", Part::One).is_none());
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(5).map(|day| day.crate_name()), Some("day05".to_string()));
+        assert!(find(25).is_none());
+    }
+
+    /// Variations of `input`: every truncation, and every character replaced by a few that tend to
+    /// confuse parsers.
+    fn mangled(input: &str) -> Vec<String> {
+        let boundaries: Vec<usize> = input.char_indices().map(|(index, _)| index).collect();
+        let truncated = boundaries.iter().map(|index| input[..*index].to_string());
+        let substituted = input.char_indices().flat_map(|(index, c)| {
+            ['0', '9', ' ', '\n', '#', '-', 'é'].into_iter()
+                .map(move |other| format!("{}{other}{}", &input[..index], &input[index + c.len_utf8()..]))
+        });
+        return truncated.chain(substituted).collect();
+    }
+
+    /// Hand-written inputs of every day in the shape of the puzzle input, the starting points of the
+    /// mangling. The puzzle's own examples are not redistributed.
+    const EXAMPLES: &[(u8, &str)] = &[
+        (1, "x2y7\n\
+            nine4oneeight\n\
+            5fives"),
+        (2, "Game 1: 2 red, 5 blue; 7 green\n\
+            Game 2: 1 green, 1 blue; 3 red, 2 blue, 4 green"),
+        (3, "12..7*3\n\
+            ..#....\n\
+            .45..8."),
+        (4, "Card 1:  3  7 11 |  7  2 11 40\n\
+            Card 2:  5  6  9 |  1  2  3  4"),
+        (5, "seeds: 3 4 20 2\n\
+            \n\
+            seed-to-soil map:\n\
+            10 0 5\n\
+            0 10 5\n\
+            \n\
+            soil-to-fertilizer map:\n\
+            2 0 3\n\
+            \n\
+            fertilizer-to-water map:\n\
+            30 20 4\n\
+            \n\
+            water-to-light map:\n\
+            0 5 5\n\
+            5 0 5\n\
+            \n\
+            light-to-temperature map:\n\
+            7 1 6\n\
+            \n\
+            temperature-to-humidity map:\n\
+            40 10 10\n\
+            \n\
+            humidity-to-location map:\n\
+            1 40 3"),
+        (6, "Time:      4  11\n\
+            Distance:  3  20"),
+        (7, "A23A4 12\n\
+            JJ9T9 305\n\
+            77787 46\n\
+            Q2KJ8 9"),
+        (8, "LLR\n\
+            \n\
+            AAA = (BBB, BBB)\n\
+            BBB = (AAA, ZZZ)\n\
+            ZZZ = (ZZZ, ZZZ)"),
+        (8, "RL\n\
+            \n\
+            PPA = (PPZ, XXX)\n\
+            PPZ = (XXX, PPA)\n\
+            QQA = (QQZ, QQZ)\n\
+            QQZ = (QQA, QQA)\n\
+            XXX = (XXX, XXX)"),
+        (9, "2 4 6 8\n\
+            -1 0 4 11 21"),
+        (10, ".S--7\n\
+            .|..|\n\
+            .L7.|\n\
+            ..L-J"),
+        (10, "F----7\n\
+            S.F7.|\n\
+            |.||.|\n\
+            L-JL-J"),
+        (11, "..#.\n\
+            ....\n\
+            #...\n\
+            ...#"),
+        (12, "?.#?? 1,2\n\
+            .??.?#? 1,1,1\n\
+            ?#??? 3"),
+    ];
+
+    #[test]
+    fn test_mangled_examples_do_not_panic() {
+        assert!(EXAMPLES.iter().all(|(number, _)| find(*number).is_some()), "an example belongs to no day");
+        for day in DAYS {
+            let examples: Vec<&str> = EXAMPLES.iter().filter(|(number, _)| *number == day.number).map(|(_, example)| *example).collect();
+            // A day fresh from `aoc new` has no example until one is written here.
+            if examples.is_empty() {
+                continue;
+            }
+            assert!(examples.iter().all(|example| day.solve(example, &[]).is_ok()), "{} does not parse its examples", day.crate_name());
+            for input in examples.into_iter().flat_map(mangled) {
+                let solved = std::panic::catch_unwind(|| day.solve(&input, &[Part::One, Part::Two]));
+                assert!(solved.is_ok(), "{} panicked on {input:?}", day.crate_name());
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+
+use aoc_common::{input, InputError};
+
+/// Environment variable pointing to the directory inputs are cached in, see [`InputCache`].
+pub const CACHE_DIR_ENV: &str = "AOC_CACHE_DIR";
+/// Environment variable holding the session token to download inputs with.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+/// Environment variable overriding the server inputs are downloaded from.
+pub const URL_ENV: &str = "AOC_URL";
+pub const DEFAULT_URL: &str = "https://adventofcode.com";
+
+/// The year of the puzzles in this repository.
+pub const YEAR: u16 = 2023;
+
+const USER_AGENT: &str = concat!("aoc-runner/", env!("CARGO_PKG_VERSION"));
+
+/// Puzzle inputs kept as `<dir>/<year>/dayNN.txt`. An input missing from the cache is downloaded
+/// once when there is a [`Fetcher`], and read from the cache ever after.
+#[derive(Debug, Clone)]
+pub struct InputCache {
+    dir: PathBuf,
+    fetcher: Option<Fetcher>,
+}
+
+impl InputCache {
+    /// A cache that only reads the inputs already in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> InputCache {
+        InputCache { dir: dir.into(), fetcher: None }
+    }
+
+    pub fn with_fetcher(self: InputCache, fetcher: Fetcher) -> InputCache {
+        InputCache { fetcher: Some(fetcher), ..self }
+    }
+
+    /// The cache in `dir`, falling back to `$AOC_CACHE_DIR`, which downloads inputs when `$AOC_SESSION`
+    /// is set. `None` when no cache directory is configured at all.
+    pub fn from_env(dir: Option<PathBuf>) -> Option<InputCache> {
+        let cache = InputCache::new(dir.or_else(|| std::env::var_os(CACHE_DIR_ENV).map(PathBuf::from))?);
+        return match Fetcher::from_env() {
+            Some(fetcher) => Some(cache.with_fetcher(fetcher)),
+            None => Some(cache),
+        };
+    }
+
+    pub fn path(self: &InputCache, year: u16, day: u8) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("day{day:02}.txt"))
+    }
+
+    /// Reads the input from the cache, downloading and storing it first when it is not there yet.
+    pub fn read(self: &InputCache, year: u16, day: u8) -> Result<String, InputError> {
+        let path = self.path(year, day);
+        return match (input::read_file(&path), &self.fetcher) {
+            (Err(InputError::Missing { .. }), Some(fetcher)) => {
+                let input = fetcher.fetch(year, day)?;
+                store(&path, &input)?;
+                Ok(input)
+            }
+            (result, _) => result,
+        };
+    }
+}
+
+/// Writes to a temporary file first, so an interrupted download never leaves a partial input behind.
+fn store(path: &Path, input: &str) -> Result<(), InputError> {
+    let io_error = |source| InputError::Io { path: path.to_path_buf(), source };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    let partial = path.with_extension("txt.partial");
+    std::fs::write(&partial, input).map_err(io_error)?;
+    return std::fs::rename(&partial, path).map_err(io_error);
+}
+
+/// Downloads inputs from the puzzle server, authenticated by a session token.
+#[derive(Clone)]
+pub struct Fetcher {
+    base_url: String,
+    session: String,
+}
+
+impl Fetcher {
+    pub fn new(base_url: impl Into<String>, session: impl Into<String>) -> Fetcher {
+        Fetcher { base_url: base_url.into(), session: session.into() }
+    }
+
+    /// Uses the session token in `$AOC_SESSION` and the server in `$AOC_URL`, `None` without a token.
+    pub fn from_env() -> Option<Fetcher> {
+        let session = std::env::var(SESSION_ENV).ok().filter(|session| !session.trim().is_empty())?;
+        let base_url = std::env::var(URL_ENV).unwrap_or(DEFAULT_URL.to_string());
+        return Some(Fetcher::new(base_url, session.trim()));
+    }
+
+    pub fn url(self: &Fetcher, year: u16, day: u8) -> String {
+        format!("{}/{year}/day/{day}/input", self.base_url.trim_end_matches('/'))
+    }
+
+    pub fn fetch(self: &Fetcher, year: u16, day: u8) -> Result<String, InputError> {
+        let url = self.url(year, day);
+        let error = |message: String| InputError::Fetch { url: url.clone(), message };
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|err| error(describe(err)))?;
+        return response.into_string().map_err(|err| error(err.to_string()));
+    }
+
+    pub fn answer_url(self: &Fetcher, year: u16, day: u8) -> String {
+        format!("{}/{year}/day/{day}/answer", self.base_url.trim_end_matches('/'))
+    }
+
+    /// Posts `answer` to `part` of the puzzle, returning the page the server responds with.
+    pub fn submit(self: &Fetcher, year: u16, day: u8, part: u8, answer: &str) -> Result<String, String> {
+        let url = self.answer_url(year, day);
+        let error = |message: String| format!("could not submit the answer to {url}: {message}");
+        let response = ureq::post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|err| error(describe(err)))?;
+        return response.into_string().map_err(|err| error(err.to_string()));
+    }
+}
+
+fn describe(err: ureq::Error) -> String {
+    return match err {
+        ureq::Error::Status(404, _) => "the puzzle is not unlocked yet".to_string(),
+        ureq::Error::Status(400 | 401, _) => format!("the session token in {SESSION_ENV} was rejected"),
+        ureq::Error::Status(status, _) => format!("the server responded with status {status}"),
+        ureq::Error::Transport(transport) => transport.to_string(),
+    };
+}
+
+/// Leaves out the session token, which is as good as a password.
+impl Debug for Fetcher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fetcher").field("base_url", &self.base_url).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test_fetch {
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread::JoinHandle;
+
+    use tiny_http::{Header, Response, Server};
+
+    use super::*;
+
+    /// A server answering the given number of requests with `status` and `body`, sending back the url
+    /// and cookie of every request it got.
+    fn serve(requests: usize, status: u16, body: &'static str) -> (String, Receiver<(String, String)>, JoinHandle<()>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+        let (sender, receiver) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            for request in server.incoming_requests().take(requests) {
+                let cookie = request.headers().iter()
+                    .find(|header| header.field.equiv("Cookie"))
+                    .map_or(String::new(), |header| header.value.to_string());
+                sender.send((request.url().to_string(), cookie)).unwrap();
+                let content_type = Header::from_bytes("Content-Type", "text/plain").unwrap();
+                request.respond(Response::from_string(body).with_status_code(status).with_header(content_type)).unwrap();
+            }
+        });
+        return (base_url, receiver, handle);
+    }
+
+    fn cache_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-runner-{test}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        return dir;
+    }
+
+    #[test]
+    fn test_fetches_once() {
+        let (base_url, requests, server) = serve(1, 200, "1abc2\n");
+        let dir = cache_dir("fetches-once");
+        let cache = InputCache::new(&dir).with_fetcher(Fetcher::new(base_url, "secret"));
+        assert_eq!(cache.read(2023, 1).unwrap(), "1abc2\n");
+        assert_eq!(requests.recv().unwrap(), ("/2023/day/1/input".to_string(), "session=secret".to_string()));
+        server.join().unwrap();
+        // The server is gone, so this can only come from the cache.
+        assert_eq!(cache.read(2023, 1).unwrap(), "1abc2\n");
+        assert_eq!(std::fs::read_to_string(dir.join("2023").join("day01.txt")).unwrap(), "1abc2\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (base_url, _requests, server) = serve(2, 404, "Please don't repeatedly request this endpoint before it unlocks!");
+        let dir = cache_dir("fetch-errors");
+        let cache = InputCache::new(&dir).with_fetcher(Fetcher::new(format!("{base_url}/"), "secret"));
+        let err = cache.read(2023, 25).unwrap_err();
+        assert_eq!(err.to_string(), format!("could not fetch input from {base_url}/2023/day/25/input: the puzzle is not unlocked yet"));
+        assert!(!cache.path(2023, 25).exists());
+        let err = cache.read(2023, 25).unwrap_err();
+        assert!(matches!(err, InputError::Fetch { .. }));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_without_fetcher() {
+        let dir = cache_dir("without-fetcher");
+        let cache = InputCache::new(&dir);
+        assert!(matches!(cache.read(2023, 3), Err(InputError::Missing { .. })));
+        std::fs::create_dir_all(dir.join("2023")).unwrap();
+        std::fs::write(cache.path(2023, 3), "467..114..").unwrap();
+        assert_eq!(cache.read(2023, 3).unwrap(), "467..114..");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_debug_hides_session() {
+        let fetcher = Fetcher::new(DEFAULT_URL, "secret");
+        assert!(!format!("{fetcher:?}").contains("secret"));
+        assert_eq!(fetcher.url(2023, 7), "https://adventofcode.com/2023/day/7/input");
+    }
+}
@@ -0,0 +1,9 @@
+pub mod answers;
+pub mod days;
+pub mod fetch;
+pub mod measure;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
+
+pub use days::{Day, Part, Solved, SolvedPart, DAYS};
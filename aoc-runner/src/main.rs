@@ -0,0 +1,381 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+use aoc_common::{input, parallel, InputError, Inputs};
+use aoc_runner::measure::{Bytes, CountingAllocator, Time};
+use aoc_runner::answers::Answers;
+use aoc_runner::fetch::{self, Fetcher, InputCache};
+use aoc_runner::report::{self, Record};
+use aoc_runner::scaffold;
+use aoc_runner::submit::{self, History, Verdict};
+use aoc_runner::{answers, days, Day, Part, Solved, DAYS};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Debug, Parser)]
+#[command(name = "aoc", about = "Runs the Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Print the spans of parsing and each part, and the events of the days, to stderr. Takes a filter
+    /// like `--trace=day08=debug,day12=trace`, and shows everything up to debug without one
+    #[arg(long, global = true, value_name = "FILTER", num_args = 0..=1, require_equals = true, default_missing_value = "debug")]
+    trace: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run one or more days, all registered days when no day is given
+    Run(RunArgs),
+    /// Check the answers of one or more days against the answers recorded in answers.toml
+    Verify(VerifyArgs),
+    /// Create the crate of a new day from the template and register it with the runner
+    New(NewArgs),
+    /// Solve a part on the real input and submit the answer, unless earlier submissions rule it out
+    Submit(SubmitArgs),
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    /// Day to run, may be repeated
+    #[arg(short, long = "day", value_parser = clap::value_parser!(u8).range(1..=25))]
+    days: Vec<u8>,
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Input file, `-` for stdin (only valid for a single day)
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+    /// Directory holding the inputs as `dayNN/input.txt`, defaults to $AOC_INPUT_DIR
+    /// and to dayNN/resources/input.txt when that is not set
+    #[arg(long, conflicts_with = "input")]
+    input_dir: Option<PathBuf>,
+    /// Directory caching the inputs as `<year>/dayNN.txt`, defaults to $AOC_CACHE_DIR. Missing inputs are
+    /// downloaded into it when $AOC_SESSION holds a session token
+    #[arg(long, conflicts_with_all = ["input", "input_dir"])]
+    cache_dir: Option<PathBuf>,
+    /// Report the time and peak allocation of parsing and each part, with a summary table for multiple days
+    #[arg(short, long, conflicts_with = "format")]
+    time: bool,
+    /// How to print the results, the machine-readable formats always include timings and input hashes
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Explain the answers line by line instead, including the lines that have no answer (day 1 only)
+    #[arg(long, conflicts_with_all = ["time", "format"])]
+    diagnose: bool,
+}
+
+#[derive(Debug, Args)]
+struct VerifyArgs {
+    /// Day to verify, may be repeated
+    #[arg(short, long = "day", value_parser = clap::value_parser!(u8).range(1..=25))]
+    days: Vec<u8>,
+    /// Directory holding the inputs and answers as `dayNN/input.txt` and `dayNN/answers.toml`
+    #[arg(long)]
+    input_dir: Option<PathBuf>,
+    /// Directory caching the real inputs, like for `run`
+    #[arg(long, conflicts_with = "input_dir")]
+    cache_dir: Option<PathBuf>,
+    /// How to print the results
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Debug, Args)]
+struct NewArgs {
+    /// Day to create
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+}
+
+#[derive(Debug, Args)]
+struct SubmitArgs {
+    /// Day to submit
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Part to submit
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+    /// Directory holding the inputs as `dayNN/input.txt`, like for `run`
+    #[arg(long)]
+    input_dir: Option<PathBuf>,
+    /// Directory caching the real inputs, like for `run`
+    #[arg(long, conflicts_with = "input_dir")]
+    cache_dir: Option<PathBuf>,
+    /// File recording every submission, defaults to $AOC_HISTORY and to submissions.toml in the workspace
+    #[arg(long)]
+    history: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(filter) = &cli.trace {
+        if let Err(message) = init_tracing(filter) {
+            eprintln!("error: {message}");
+            return ExitCode::FAILURE;
+        }
+    }
+    let result = match cli.command {
+        Command::Run(args) => run(args),
+        Command::Verify(args) => verify(args),
+        Command::New(args) => new(args),
+        Command::Submit(args) => submit(args),
+    };
+    return match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    };
+}
+
+/// Reports every span with its duration when it closes, next to the events it saw.
+fn init_tracing(filter: &str) -> Result<(), String> {
+    let filter = EnvFilter::try_new(filter).map_err(|err| format!("invalid --trace filter `{filter}`: {err}"))?;
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .init();
+    return Ok(());
+}
+
+fn run(args: RunArgs) -> Result<(), String> {
+    let selected = select_days(&args.days)?;
+    if args.input.is_some() && selected.len() != 1 {
+        return Err("--input can only be used together with a single --day".to_string());
+    }
+    let parts = match args.part.and_then(Part::from_number) {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    };
+
+    if args.format != Format::Text {
+        return run_records(&args, &selected, &parts);
+    }
+    if args.diagnose {
+        return diagnose(&args, &selected, &parts);
+    }
+
+    let solved_days = solve_days(&selected, args.time, |day| {
+        let input = read_input(&args, day).map_err(|err| format!("day {}: {err}", day.number))?;
+        return day.solve(&input, &parts)
+            .map_err(|err| format!("day {}: {}", day.number, err.render(&input)));
+    });
+    let mut summary = Vec::new();
+    for (day, solved) in selected.into_iter().zip(solved_days) {
+        let solved = solved?;
+        match args.time {
+            true => println!("day {:02}  (parse: {})", day.number, solved.parse),
+            false => println!("day {:02}", day.number),
+        }
+        for part in &solved.parts {
+            match args.time {
+                true => println!("  part {}: {}  ({})", part.part.number(), part.answer, part.measurement),
+                false => println!("  part {}: {}", part.part.number(), part.answer),
+            }
+        }
+        summary.push((day, solved));
+    }
+    if args.time && summary.len() > 1 {
+        print_summary(&summary);
+    }
+    return Ok(());
+}
+
+/// Unlike the text output, a day that fails doesn't stop the run, but is reported with an error status.
+fn run_records(args: &RunArgs, selected: &[&Day], parts: &[Part]) -> Result<(), String> {
+    let mut records = Vec::new();
+    for day in selected {
+        let (input_name, answers) = match &args.input {
+            Some(path) => (path.display().to_string(), Answers::default()),
+            None => ("input".to_string(), Answers::read(&day_inputs(day, &args.input_dir))?),
+        };
+        let checks = answers::check(day, &input_name, read_input(args, day), &answers, parts);
+        records.extend(checks.iter().map(Record::from));
+    }
+    write_records(&records, args.format)?;
+    return match records.iter().filter(|record| record.status == "error").count() {
+        0 => Ok(()),
+        errors => Err(format!("{errors} part(s) could not be solved")),
+    };
+}
+
+/// Prints how every line of the input makes up the answer of each part, and fails when any line has none.
+fn diagnose(args: &RunArgs, selected: &[&Day], parts: &[Part]) -> Result<(), String> {
+    if let Some(day) = selected.iter().find(|day| !day.has_diagnostics()) {
+        return Err(format!("day {} cannot explain its answers, select a day that can with --day", day.number));
+    }
+    let mut errors = 0;
+    for day in selected {
+        let input = read_input(args, day).map_err(|err| format!("day {}: {err}", day.number))?;
+        for part in parts {
+            println!("day {:02} part {}", day.number, part.number());
+            let lines = day.diagnose(&input, *part).transpose().map_err(|err| format!("day {}: {err}", day.number))?;
+            for line in lines.unwrap_or_default() {
+                match line {
+                    Ok(line) => println!("  {line}"),
+                    Err(line) => {
+                        println!("  error: {line}");
+                        errors += 1;
+                    }
+                }
+            }
+        }
+    }
+    return match errors {
+        0 => Ok(()),
+        errors => Err(format!("{errors} line(s) have no answer")),
+    };
+}
+
+/// Solves every day in `days`, all at once with the `parallel` feature unless the days are `measured`,
+/// as the clock and the allocation counter need the process to themselves. Keeps the order of `days`.
+fn solve_days<R: Send>(days: &[&'static Day], measured: bool, solve: impl Fn(&Day) -> R + Sync + Send) -> Vec<R> {
+    return match measured {
+        true => days.iter().map(|day| solve(day)).collect(),
+        false => parallel::map(days, |day| solve(day)),
+    };
+}
+
+fn read_input(args: &RunArgs, day: &Day) -> Result<String, InputError> {
+    return match &args.input {
+        Some(path) => input::read_file(path),
+        None => real_input(day, &args.input_dir, &args.cache_dir),
+    };
+}
+
+/// Reads the real input from `--input-dir` when given, from the input cache when one is configured,
+/// and from the day's own inputs otherwise.
+fn real_input(day: &Day, input_dir: &Option<PathBuf>, cache_dir: &Option<PathBuf>) -> Result<String, InputError> {
+    if input_dir.is_none() {
+        if let Some(cache) = InputCache::from_env(cache_dir.clone()) {
+            return cache.read(fetch::YEAR, day.number);
+        }
+    }
+    return day_inputs(day, input_dir).read("input.txt");
+}
+
+fn day_inputs(day: &Day, input_dir: &Option<PathBuf>) -> Inputs {
+    return match input_dir {
+        Some(dir) => Inputs::in_dir(dir, &day.crate_name()),
+        None => day.inputs(),
+    };
+}
+
+fn write_records(records: &[Record], format: Format) -> Result<(), String> {
+    let stdout = std::io::stdout().lock();
+    return match format {
+        Format::Json => report::write_json(records, stdout),
+        Format::Csv => report::write_csv(records, stdout),
+        Format::Text => unreachable!("text output is not made of records"),
+    };
+}
+
+/// One row per day with the time of each phase, and the share of the total runtime the day takes up.
+fn print_summary(summary: &[(&Day, Solved)]) {
+    let total: Duration = summary.iter().map(|(_, solved)| solved.total().duration).sum();
+    println!();
+    println!("{:<5} {:>10} {:>10} {:>10} {:>10} {:>6} {:>11}", "day", "parse", "part 1", "part 2", "total", "share", "peak");
+    for (day, solved) in summary {
+        let part = |part: Part| solved.parts.iter()
+            .find(|solved_part| solved_part.part == part)
+            .map_or("-".to_string(), |solved_part| Time(solved_part.measurement.duration).to_string());
+        let day_total = solved.total();
+        let share = 100.0 * day_total.duration.as_secs_f64() / total.as_secs_f64().max(f64::MIN_POSITIVE);
+        println!(
+            "{:<5} {:>10} {:>10} {:>10} {:>10} {:>5.1}% {:>11}",
+            format!("{:02}", day.number), Time(solved.parse.duration).to_string(), part(Part::One), part(Part::Two),
+            Time(day_total.duration).to_string(), share, Bytes(day_total.peak_bytes).to_string(),
+        );
+    }
+    println!("{:<5} {:>10} {:>10} {:>10} {:>10}", "all", "", "", "", Time(total).to_string());
+}
+
+fn verify(args: VerifyArgs) -> Result<(), String> {
+    let selected = select_days(&args.days)?;
+    // The machine-readable formats include the timings of the checks.
+    let checks_of_days = solve_days(&selected, args.format != Format::Text, |day| {
+        let inputs = day_inputs(day, &args.input_dir);
+        return answers::verify(day, &inputs, |input_name| match input_name {
+            "input" => real_input(day, &args.input_dir, &args.cache_dir),
+            _ => inputs.read(&format!("{input_name}.txt")),
+        });
+    });
+    let mut checks = Vec::new();
+    for day_checks in checks_of_days {
+        if args.format == Format::Text {
+            for check in &day_checks {
+                println!("day {:02}  {:<10} part {}  {}", check.day, check.input_name, check.part.number(), check.status);
+            }
+        }
+        checks.extend(day_checks);
+    }
+    if args.format != Format::Text {
+        write_records(&checks.iter().map(Record::from).collect::<Vec<Record>>(), args.format)?;
+    }
+    return match checks.iter().filter(|check| check.status.is_failure()).count() {
+        0 => Ok(()),
+        failures => Err(format!("{failures} check(s) failed")),
+    };
+}
+
+fn new(args: NewArgs) -> Result<(), String> {
+    let root = scaffold::workspace_root();
+    for path in scaffold::new_day(&root, args.day)? {
+        println!("wrote {}", path.strip_prefix(&root).unwrap_or(&path).display());
+    }
+    return Ok(());
+}
+
+/// Answers posted to `$AOC_URL` with the session token in `$AOC_SESSION`, every response is recorded
+/// in the history before it is reported.
+fn submit(args: SubmitArgs) -> Result<(), String> {
+    let day = days::find(args.day).ok_or(format!("day {} is not implemented", args.day))?;
+    let part = Part::from_number(args.part).expect("clap checks the part");
+    let path = History::path(args.history, &scaffold::workspace_root());
+    let mut history = History::read(&path)?;
+    let fetcher = Fetcher::from_env().ok_or(format!("submitting needs a session token in {}", fetch::SESSION_ENV))?;
+
+    let input = real_input(day, &args.input_dir, &args.cache_dir).map_err(|err| format!("day {}: {err}", day.number))?;
+    let solved = day.solve(&input, &[part]).map_err(|err| format!("day {}: {}", day.number, err.render(&input)))?;
+    let answer = &solved.parts[0].answer;
+    let label = format!("day {} part {}", day.number, part.number());
+    let response = submit::submit(&fetcher, &mut history, day.number, part, answer, submit::now())
+        .map_err(|err| format!("{label}: not submitted, {err}"))?;
+    history.write(&path)?;
+    let prefix = format!("{label}: {answer}");
+
+    let wait = response.wait.map_or(String::new(), |wait| format!(", wait {wait}s before submitting again"));
+    return match response.verdict {
+        Verdict::Correct => {
+            println!("{prefix} is correct");
+            Ok(())
+        }
+        verdict if verdict.is_wrong() => Err(format!("{prefix} is {verdict}{wait}")),
+        verdict => Err(format!("{prefix} was {verdict}{wait}")),
+    };
+}
+
+fn select_days(numbers: &[u8]) -> Result<Vec<&'static Day>, String> {
+    if numbers.is_empty() {
+        return Ok(DAYS.iter().collect());
+    }
+    return numbers.iter()
+        .map(|number| days::find(*number).ok_or(format!("day {number} is not implemented")))
+        .collect();
+}
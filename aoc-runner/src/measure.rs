@@ -0,0 +1,154 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator to keep track of the peak number of bytes allocated.
+/// Only takes effect when installed with `#[global_allocator]`, without it all peaks are reported as 0.
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn add(size: usize) {
+        let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+    }
+
+    fn sub(size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            CountingAllocator::add(layout.size());
+        }
+        return ptr;
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            CountingAllocator::add(layout.size());
+        }
+        return ptr;
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CountingAllocator::sub(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CountingAllocator::sub(layout.size());
+            CountingAllocator::add(new_size);
+        }
+        return new_ptr;
+    }
+}
+
+/// Wall-clock time and peak allocation of a single phase of solving a day.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Measurement {
+    pub duration: Duration,
+    /// Most bytes allocated at any one time during the phase, on top of what was allocated before it.
+    pub peak_bytes: usize,
+}
+
+/// Runs `f` and measures it. Allocations by other threads are counted as well.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Measurement) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    let duration = start.elapsed();
+    let peak_bytes = PEAK.load(Ordering::Relaxed).saturating_sub(before);
+    return (result, Measurement { duration, peak_bytes });
+}
+
+impl Measurement {
+    pub fn total<'a>(measurements: impl IntoIterator<Item = &'a Measurement>) -> Measurement {
+        return measurements.into_iter().fold(Measurement::default(), |total, measurement| Measurement {
+            duration: total.duration + measurement.duration,
+            peak_bytes: total.peak_bytes.max(measurement.peak_bytes),
+        });
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}", Time(self.duration), Bytes(self.peak_bytes))
+    }
+}
+
+/// A duration rendered in the most readable unit, e.g. `12.34ms`.
+#[derive(Debug, Clone, Copy)]
+pub struct Time(pub Duration);
+
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let nanos = self.0.as_nanos() as f64;
+        let text = match nanos {
+            n if n < 1e3 => format!("{n}ns"),
+            n if n < 1e6 => format!("{:.2}µs", n / 1e3),
+            n if n < 1e9 => format!("{:.2}ms", n / 1e6),
+            n => format!("{:.2}s", n / 1e9),
+        };
+        f.pad(&text)
+    }
+}
+
+/// A number of bytes rendered in binary units, e.g. `1.50 KiB`.
+#[derive(Debug, Clone, Copy)]
+pub struct Bytes(pub usize);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let bytes = self.0 as f64;
+        let text = match bytes {
+            b if b < 1024.0 => format!("{b} B"),
+            b if b < 1024.0 * 1024.0 => format!("{:.2} KiB", b / 1024.0),
+            b if b < 1024.0 * 1024.0 * 1024.0 => format!("{:.2} MiB", b / (1024.0 * 1024.0)),
+            b => format!("{:.2} GiB", b / (1024.0 * 1024.0 * 1024.0)),
+        };
+        f.pad(&text)
+    }
+}
+
+#[cfg(test)]
+mod test_measure {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure_peak() {
+        let (len, measurement) = measure(|| vec![0u8; 1 << 20].len());
+        assert_eq!(len, 1 << 20);
+        assert!(measurement.peak_bytes >= 1 << 20);
+    }
+
+    #[test]
+    fn test_total() {
+        let measurements = [
+            Measurement { duration: Duration::from_millis(2), peak_bytes: 10 },
+            Measurement { duration: Duration::from_millis(3), peak_bytes: 30 },
+        ];
+        assert_eq!(Measurement::total(&measurements), Measurement { duration: Duration::from_millis(5), peak_bytes: 30 });
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Time(Duration::from_nanos(999)).to_string(), "999ns");
+        assert_eq!(Time(Duration::from_micros(1500)).to_string(), "1.50ms");
+        assert_eq!(Bytes(512).to_string(), "512 B");
+        assert_eq!(Bytes(1536).to_string(), "1.50 KiB");
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::Write;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::answers::{Check, Status};
+
+/// One row of machine-readable output: a single part of a day solved on a single input.
+/// Fields that could not be determined, like the answer of an input that failed to parse, are left empty.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub input: String,
+    pub part: u8,
+    pub answer: Option<String>,
+    pub parse_ns: Option<u64>,
+    pub duration_ns: Option<u64>,
+    pub peak_bytes: Option<usize>,
+    /// SHA-256 of the input, so runs on different inputs are not compared by accident.
+    pub input_hash: Option<String>,
+    pub status: &'static str,
+    /// What went wrong, for the statuses that carry more than their name.
+    pub detail: Option<String>,
+}
+
+impl From<&Check> for Record {
+    fn from(check: &Check) -> Record {
+        let detail = match &check.status {
+            Status::Fail { expected, .. } => Some(format!("expected {expected}")),
+            Status::Error(message) => Some(message.clone()),
+            _ => None,
+        };
+        return Record {
+            day: check.day,
+            input: check.input_name.clone(),
+            part: check.part.number(),
+            answer: check.solved.as_ref().map(|solved| solved.answer.clone()),
+            parse_ns: check.parse.map(|parse| parse.duration.as_nanos() as u64),
+            duration_ns: check.solved.as_ref().map(|solved| solved.measurement.duration.as_nanos() as u64),
+            peak_bytes: check.solved.as_ref().map(|solved| solved.measurement.peak_bytes),
+            input_hash: check.input_hash.clone(),
+            status: check.status.name(),
+            detail,
+        };
+    }
+}
+
+pub fn input_hash(input: &str) -> String {
+    return format!("{:x}", Sha256::digest(input.as_bytes()));
+}
+
+/// Writes the records as a single JSON array, one object per record.
+pub fn write_json(records: &[Record], writer: impl Write) -> Result<(), String> {
+    let mut writer = writer;
+    serde_json::to_writer_pretty(&mut writer, records).map_err(|err| err.to_string())?;
+    return writeln!(writer).map_err(|err| err.to_string());
+}
+
+/// Writes the records as CSV with a header row, empty fields standing in for missing values.
+pub fn write_csv(records: &[Record], writer: impl Write) -> Result<(), String> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for record in records {
+        writer.serialize(record).map_err(|err| err.to_string())?;
+    }
+    return writer.flush().map_err(|err| err.to_string());
+}
+
+#[cfg(test)]
+mod test_report {
+    use super::*;
+    use crate::Part;
+
+    fn record(status: Status) -> Record {
+        let check = Check {
+            day: 5,
+            input_name: "input".to_string(),
+            part: Part::Two,
+            status,
+            input_hash: None,
+            parse: None,
+            solved: None,
+        };
+        return Record::from(&check);
+    }
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let records = [record(Status::MissingInput), record(Status::Error("invalid, input".to_string()))];
+        let mut output = Vec::new();
+        write_csv(&records, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\
+day,input,part,answer,parse_ns,duration_ns,peak_bytes,input_hash,status,detail
+5,input,2,,,,,,missing-input,
+5,input,2,,,,,,error,\"invalid, input\"
+");
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut output = Vec::new();
+        write_json(&[record(Status::MissingInput)], &mut output).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json[0]["day"], 5);
+        assert_eq!(json[0]["status"], "missing-input");
+        assert!(json[0]["answer"].is_null());
+    }
+}
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+const CARGO_TEMPLATE: &str = include_str!("../templates/Cargo.toml.tmpl");
+const LIB_TEMPLATE: &str = include_str!("../templates/lib.rs.tmpl");
+const MAIN_TEMPLATE: &str = include_str!("../templates/main.rs.tmpl");
+const ANSWERS_TEMPLATE: &str = include_str!("../templates/answers.toml.tmpl");
+const FUZZ_TARGET_TEMPLATE: &str = include_str!("../templates/fuzz_target.rs.tmpl");
+
+/// The root of the workspace the runner was built in.
+pub fn workspace_root() -> PathBuf {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+    return root.canonicalize().unwrap_or(root);
+}
+
+/// A list of days in one of the workspace's files, one entry per day, kept in order.
+struct Registry {
+    path: &'static str,
+    /// What a line of the entry of a day starts with, up to its number.
+    prefix: &'static str,
+    /// Whether the entries are blocks of lines separated by a blank line, rather than single lines.
+    blocks: bool,
+    line: fn(u8) -> String,
+}
+
+const REGISTRIES: [Registry; 5] = [
+    Registry {
+        path: "aoc-runner/Cargo.toml",
+        prefix: "day",
+        blocks: false,
+        line: |day| format!("day{day:02} = {{ path = \"../day{day:02}\" }}"),
+    },
+    Registry {
+        path: "aoc-runner/src/days.rs",
+        prefix: "Day::of::<day",
+        blocks: false,
+        line: |day| format!("Day::of::<day{day:02}::Day{day:02}>(),"),
+    },
+    Registry {
+        path: "aoc-runner/benches/days.rs",
+        prefix: "bench_day::<day",
+        blocks: false,
+        line: |day| format!("bench_day::<day{day:02}::Day{day:02}>,"),
+    },
+    Registry {
+        path: "fuzz/Cargo.toml",
+        prefix: "day",
+        blocks: false,
+        line: |day| format!("day{day:02} = {{ path = \"../day{day:02}\" }}"),
+    },
+    Registry {
+        path: "fuzz/Cargo.toml",
+        prefix: "name = \"day",
+        blocks: true,
+        line: |day| format!("[[bin]]\nname = \"day{day:02}\"\npath = \"fuzz_targets/day{day:02}.rs\"\ntest = false\ndoc = false\nbench = false"),
+    },
+];
+
+/// Creates the crate of `day` under `root` from the templates, with empty resource files, and registers
+/// it with the runner and the fuzz targets. Returns the files created and changed. Nothing is written when
+/// the day exists.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let crate_dir = root.join(format!("day{day:02}"));
+    if crate_dir.exists() {
+        return Err(format!("{} already exists", crate_dir.display()));
+    }
+    // A file may hold several registries, each one registering the day in what the previous ones left.
+    let mut registries: Vec<(PathBuf, String)> = vec![];
+    for registry in &REGISTRIES {
+        let path = root.join(registry.path);
+        let position = match registries.iter().position(|(registered, _)| *registered == path) {
+            Some(position) => position,
+            None => {
+                let content = std::fs::read_to_string(&path).map_err(|err| format!("could not read {}: {err}", path.display()))?;
+                registries.push((path.clone(), content));
+                registries.len() - 1
+            }
+        };
+        let registered = &mut registries[position].1;
+        *registered = register(registered, day, registry).map_err(|err| format!("{}: {err}", path.display()))?;
+    }
+
+    let fill = |template: &str| template.replace("__NN__", &format!("{day:02}")).replace("__N__", &day.to_string());
+    let files = [
+        (crate_dir.join("Cargo.toml"), fill(CARGO_TEMPLATE)),
+        (crate_dir.join("src/lib.rs"), fill(LIB_TEMPLATE)),
+        (crate_dir.join("src/main.rs"), fill(MAIN_TEMPLATE)),
+        (crate_dir.join("resources/answers.toml"), fill(ANSWERS_TEMPLATE)),
+        (crate_dir.join("resources/example.txt"), String::new()),
+        (crate_dir.join("resources/input.txt"), String::new()),
+        (root.join(format!("fuzz/fuzz_targets/day{day:02}.rs")), fill(FUZZ_TARGET_TEMPLATE)),
+    ];
+    let mut written = vec![];
+    for (name, content) in files.into_iter().chain(registries) {
+        if let Some(dir) = name.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| format!("could not create {}: {err}", dir.display()))?;
+        }
+        std::fs::write(&name, content).map_err(|err| format!("could not write {}: {err}", name.display()))?;
+        written.push(name);
+    }
+    return Ok(written);
+}
+
+/// Adds the entry of `day` to `content`, after the last day before it, indented like the other days.
+fn register(content: &str, day: u8, registry: &Registry) -> Result<String, String> {
+    let separator = if registry.blocks { "\n\n" } else { "\n" };
+    let entries: Vec<&str> = content.strip_suffix('\n').unwrap_or(content).split(separator).collect();
+    let days: Vec<(usize, u8)> = entries.iter().enumerate()
+        .filter_map(|(index, entry)| {
+            let number = entry.lines().find_map(|line| line.trim_start().strip_prefix(registry.prefix)?.get(..2)?.parse().ok())?;
+            return Some((index, number));
+        })
+        .collect();
+    let Some(&(first, _)) = days.first() else {
+        return Err("no days are registered here".to_string());
+    };
+    if days.iter().any(|(_, number)| *number == day) {
+        return Err(format!("day {day} is registered already"));
+    }
+    let index = days.iter().rfind(|(_, number)| *number < day).map_or(first, |(index, _)| index + 1);
+    let indent = &entries[first][..entries[first].len() - entries[first].trim_start().len()];
+    let mut entries: Vec<String> = entries.into_iter().map(str::to_string).collect();
+    entries.insert(index, format!("{indent}{}", (registry.line)(day)));
+    return Ok(entries.join(separator) + if content.ends_with('\n') { "\n" } else { "" });
+}
+
+#[cfg(test)]
+mod test_scaffold {
+    use super::*;
+
+    #[test]
+    fn test_register_in_order() {
+        let content = "DAYS = [\n    Day::of::<day01::Day01>(),\n    Day::of::<day03::Day03>(),\n];\n";
+        let registered = register(content, 2, &REGISTRIES[1]).unwrap();
+        assert_eq!(registered, "DAYS = [\n    Day::of::<day01::Day01>(),\n    Day::of::<day02::Day02>(),\n    Day::of::<day03::Day03>(),\n];\n");
+        let registered = register(content, 13, &REGISTRIES[1]).unwrap();
+        assert!(registered.ends_with("    Day::of::<day13::Day13>(),\n];\n"));
+        assert_eq!(register(content, 3, &REGISTRIES[1]).unwrap_err(), "day 3 is registered already");
+        assert_eq!(register("[dependencies]\n", 3, &REGISTRIES[0]).unwrap_err(), "no days are registered here");
+        let bins = "[workspace]\n\n[[bin]]\nname = \"day03\"\n";
+        let registered = register(bins, 2, &REGISTRIES[4]).unwrap();
+        assert!(registered.starts_with("[workspace]\n\n[[bin]]\nname = \"day02\"\npath = \"fuzz_targets/day02.rs\"\n"));
+        assert!(registered.ends_with("bench = false\n\n[[bin]]\nname = \"day03\"\n"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-runner-scaffold-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for registry in &REGISTRIES {
+            let path = root.join(registry.path);
+            if path.exists() {
+                continue;
+            }
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::copy(workspace_root().join(registry.path), path).unwrap();
+        }
+
+        let fuzz_manifest = std::fs::read_to_string(root.join("fuzz/Cargo.toml")).unwrap();
+        let last_dependency = fuzz_manifest.lines().rfind(|line| line.starts_with("day") && line.contains("= { path")).unwrap().to_string();
+
+        let written = new_day(&root, 24).unwrap();
+        assert_eq!(written.len(), 11);
+        let lib = std::fs::read_to_string(root.join("day24/src/lib.rs")).unwrap();
+        assert!(lib.contains("impl Solution for Day24 {\n    const DAY: u8 = 24;"));
+        assert!(lib.contains("mod test_day24 {"));
+        assert_eq!(std::fs::read_to_string(root.join("day24/resources/input.txt")).unwrap(), "");
+        let days = std::fs::read_to_string(root.join("aoc-runner/src/days.rs")).unwrap();
+        assert!(days.contains("    Day::of::<day24::Day24>(),\n];"));
+        let manifest = std::fs::read_to_string(root.join("aoc-runner/Cargo.toml")).unwrap();
+        assert!(manifest.contains("day24 = { path = \"../day24\" }\n\n[dev-dependencies]"));
+        let fuzz_manifest = std::fs::read_to_string(root.join("fuzz/Cargo.toml")).unwrap();
+        assert!(fuzz_manifest.contains(&format!("{last_dependency}\nday24 = {{ path = \"../day24\" }}\n")));
+        assert!(fuzz_manifest.ends_with("bench = false\n\n[[bin]]\nname = \"day24\"\npath = \"fuzz_targets/day24.rs\"\ntest = false\ndoc = false\nbench = false\n"));
+        let target = std::fs::read_to_string(root.join("fuzz/fuzz_targets/day24.rs")).unwrap();
+        assert!(target.contains("aoc_fuzz::solve::<day24::Day24>(data)"));
+
+        assert_eq!(new_day(&root, 24).unwrap_err(), format!("{} already exists", root.join("day24").display()));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,351 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::{Fetcher, YEAR};
+use crate::Part;
+
+/// Environment variable pointing to the history of submitted answers, see [`History`].
+pub const HISTORY_ENV: &str = "AOC_HISTORY";
+
+/// What the server made of a submitted answer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint which way.
+    Incorrect,
+    /// Not judged, an answer was submitted too recently.
+    Wait,
+    /// Not judged, the part is solved already or part 1 is not.
+    WrongLevel,
+    /// The response had no verdict we know of.
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too high",
+            Verdict::TooLow => "too low",
+            Verdict::Incorrect => "incorrect",
+            Verdict::Wait => "not judged, an answer was submitted too recently",
+            Verdict::WrongLevel => "not judged, the part is solved already or cannot be solved yet",
+            Verdict::Unknown => "answered without a verdict we know of",
+        };
+        write!(f, "{text}")
+    }
+}
+
+impl Verdict {
+    pub fn is_wrong(self: &Verdict) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect)
+    }
+}
+
+/// The verdict on a submitted answer, with how many seconds the server wants us to wait before the next one.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Response {
+    pub verdict: Verdict,
+    pub wait: Option<u64>,
+}
+
+impl Response {
+    /// Reads the verdict from the page the server responds with, `None` when it has none.
+    pub fn parse(page: &str) -> Option<Response> {
+        let page = page.to_lowercase();
+        let verdict = if page.contains("that's the right answer") {
+            Verdict::Correct
+        } else if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else if page.contains("that's not the right answer") {
+            Verdict::Incorrect
+        } else if page.contains("you gave an answer too recently") {
+            Verdict::Wait
+        } else if page.contains("you don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            return None;
+        };
+        return Some(Response { verdict, wait: parse_wait(&page) });
+    }
+}
+
+/// Finds either `you have 4m 32s left to wait` or `please wait 5 minutes before trying again`.
+fn parse_wait(page: &str) -> Option<u64> {
+    if let Some((before, _)) = page.split_once(" left to wait") {
+        let (_, left) = before.rsplit_once("you have ")?;
+        return left.split_whitespace()
+            .map(|amount| {
+                let (number, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+                let seconds: u64 = match unit {
+                    "h" => 3600,
+                    "m" => 60,
+                    "s" => 1,
+                    _ => return None,
+                };
+                return number.parse::<u64>().ok()?.checked_mul(seconds);
+            })
+            .try_fold(0, |total: u64, seconds| total.checked_add(seconds?));
+    }
+    let (_, after) = page.split_once("please wait ")?;
+    let mut words = after.split_whitespace();
+    let number = match words.next()? {
+        "one" | "a" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "ten" => 10,
+        number => number.parse().ok()?,
+    };
+    return match words.next()? {
+        "second" | "seconds" => Some(number),
+        "minute" | "minutes" => number.checked_mul(60),
+        _ => None,
+    };
+}
+
+/// An answer that was submitted, with the response and when it came in, in seconds since the Unix epoch.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait: Option<u64>,
+}
+
+/// Every answer submitted so far, kept in a TOML file so answers known to be wrong are never submitted
+/// twice and the wait the server asks for is respected.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default, rename = "submission")]
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    /// The file given, falling back to `$AOC_HISTORY` and to `submissions.toml` in `root`.
+    pub fn path(path: Option<PathBuf>, root: &Path) -> PathBuf {
+        return path
+            .or_else(|| std::env::var_os(HISTORY_ENV).map(PathBuf::from))
+            .unwrap_or_else(|| root.join("submissions.toml"));
+    }
+
+    /// Reads the history in `path`, which is empty when the file does not exist yet.
+    pub fn read(path: &Path) -> Result<History, String> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(History::default()),
+            Err(err) => return Err(format!("could not read {}: {err}", path.display())),
+        };
+        return toml::from_str(&content).map_err(|err| format!("invalid history {}: {err}", path.display()));
+    }
+
+    pub fn write(self: &History, path: &Path) -> Result<(), String> {
+        let content = toml::to_string(self).map_err(|err| format!("could not write {}: {err}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|err| format!("could not create {}: {err}", dir.display()))?;
+        }
+        return std::fs::write(path, content).map_err(|err| format!("could not write {}: {err}", path.display()));
+    }
+
+    pub fn submissions(self: &History, day: u8, part: Part) -> impl Iterator<Item = &Submission> {
+        return self.submissions.iter().filter(move |submission| submission.day == day && submission.part == part.number());
+    }
+
+    pub fn record(self: &mut History, submission: Submission) {
+        self.submissions.push(submission);
+    }
+
+    /// Fails with the reason when submitting `answer` at `now` would be pointless: the part is solved, the
+    /// answer was wrong before or lies outside the bounds earlier answers set, or the server asked to wait.
+    /// The wait holds for every day and part, a wait beyond the largest time holds forever.
+    pub fn check(self: &History, day: u8, part: Part, answer: &str, now: u64) -> Result<(), String> {
+        let submissions: Vec<&Submission> = self.submissions(day, part).collect();
+        if let Some(correct) = submissions.iter().find(|submission| submission.verdict == Verdict::Correct) {
+            return Err(format!("{} is the correct answer already", correct.answer));
+        }
+        if let Some(wrong) = submissions.iter().find(|submission| submission.answer == answer && submission.verdict.is_wrong()) {
+            return Err(format!("{answer} was submitted before and is {}", wrong.verdict));
+        }
+        if let Ok(number) = answer.parse::<i128>() {
+            let bound = |verdict: Verdict| submissions.iter()
+                .filter(move |submission| submission.verdict == verdict)
+                .filter_map(|submission| submission.answer.parse::<i128>().ok());
+            if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| number >= *high) {
+                return Err(format!("{answer} is too high, as {high} was too high already"));
+            }
+            if let Some(low) = bound(Verdict::TooLow).max().filter(|low| number <= *low) {
+                return Err(format!("{answer} is too low, as {low} was too low already"));
+            }
+        }
+        // `Some(None)` when a wait overflows, which outlasts any other.
+        let waiting_until = self.submissions.iter()
+            .filter_map(|submission| Some(submission.at.checked_add(submission.wait?)))
+            .reduce(|until, other| until.zip(other).map(|(until, other)| until.max(other)));
+        match waiting_until {
+            Some(None) => return Err("the server asked to wait indefinitely before submitting again".to_string()),
+            Some(Some(until)) if now < until => {
+                return Err(format!("the server asked to wait another {}s before submitting again", until - now));
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+}
+
+pub fn now() -> u64 {
+    return SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+}
+
+/// Submits `answer` unless the history rules it out, and records the response in the history, with an
+/// unknown verdict when it has none we know of.
+pub fn submit(fetcher: &Fetcher, history: &mut History, day: u8, part: Part, answer: &str, now: u64) -> Result<Response, String> {
+    history.check(day, part, answer, now)?;
+    let page = fetcher.submit(YEAR, day, part.number(), answer)?;
+    let response = Response::parse(&page)
+        .unwrap_or_else(|| Response { verdict: Verdict::Unknown, wait: parse_wait(&page.to_lowercase()) });
+    history.record(Submission {
+        day,
+        part: part.number(),
+        answer: answer.to_string(),
+        verdict: response.verdict,
+        at: now,
+        wait: response.wait,
+    });
+    return Ok(response);
+}
+
+#[cfg(test)]
+mod test_submit {
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread::JoinHandle;
+
+    use tiny_http::{Response as HttpResponse, Server};
+
+    use super::*;
+
+    /// A server answering one request per page, in order, sending back the url and form of every request.
+    fn serve(pages: Vec<&'static str>) -> (String, Receiver<(String, String)>, JoinHandle<()>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.server_addr());
+        let (sender, receiver) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            for (page, mut request) in pages.into_iter().zip(server.incoming_requests()) {
+                let mut form = String::new();
+                request.as_reader().read_to_string(&mut form).unwrap();
+                sender.send((request.url().to_string(), form)).unwrap();
+                request.respond(HttpResponse::from_string(page)).unwrap();
+            }
+        });
+        return (base_url, receiver, handle);
+    }
+
+    fn submission(part: u8, answer: &str, verdict: Verdict, at: u64, wait: Option<u64>) -> Submission {
+        return Submission { day: 5, part, answer: answer.to_string(), verdict, at, wait };
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let page = "<article><p>That's not the right answer; your answer is too low. If you're stuck, ... \
+            Please wait one minute before trying again. <a href=\"/2023/day/5\">[Return to Day 5]</a></p></article>";
+        assert_eq!(Response::parse(page), Some(Response { verdict: Verdict::TooLow, wait: Some(60) }));
+        let page = "<p>You gave an answer too recently; you have to wait after submitting an answer before trying \
+            again.  You have 4m 32s left to wait.</p>";
+        assert_eq!(Response::parse(page), Some(Response { verdict: Verdict::Wait, wait: Some(272) }));
+        assert_eq!(Response::parse("<p>That's the right answer!  You are one gold star closer.</p>"),
+            Some(Response { verdict: Verdict::Correct, wait: None }));
+        assert_eq!(Response::parse("please wait 5 minutes before trying again. That's not the right answer."),
+            Some(Response { verdict: Verdict::Incorrect, wait: Some(300) }));
+        assert_eq!(Response::parse("<p>You don't seem to be solving the right level.</p>").map(|r| r.verdict), Some(Verdict::WrongLevel));
+        assert_eq!(Response::parse("<p>Log in</p>"), None);
+        assert_eq!(parse_wait("you have 99999999999999999999h left to wait"), None);
+        assert_eq!(parse_wait("you have 5124095576030431h 1000s left to wait"), None);
+        assert_eq!(parse_wait("you have 1h 2m 3s left to wait"), Some(3723));
+        assert_eq!(parse_wait("please wait 307445734561825861 minutes before trying again"), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut history = History::default();
+        history.record(submission(1, "20", Verdict::TooLow, 100, Some(60)));
+        history.record(submission(1, "90", Verdict::TooHigh, 200, Some(60)));
+        history.record(submission(1, "abc", Verdict::Incorrect, 300, None));
+        history.record(submission(2, "7", Verdict::Correct, 400, None));
+
+        assert_eq!(history.check(5, Part::One, "50", 1000), Ok(()));
+        assert_eq!(history.check(5, Part::One, "abc", 1000).unwrap_err(), "abc was submitted before and is incorrect");
+        assert_eq!(history.check(5, Part::One, "90", 1000).unwrap_err(), "90 was submitted before and is too high");
+        assert_eq!(history.check(5, Part::One, "91", 1000).unwrap_err(), "91 is too high, as 90 was too high already");
+        assert_eq!(history.check(5, Part::One, "-3", 1000).unwrap_err(), "-3 is too low, as 20 was too low already");
+        assert_eq!(history.check(5, Part::One, "50", 230).unwrap_err(), "the server asked to wait another 30s before submitting again");
+        assert_eq!(history.check(5, Part::Two, "8", 1000).unwrap_err(), "7 is the correct answer already");
+        assert_eq!(history.check(6, Part::One, "90", 230).unwrap_err(), "the server asked to wait another 30s before submitting again");
+        assert_eq!(history.check(6, Part::One, "90", 1000), Ok(()));
+
+        let page = "<p>You gave an answer too recently; you have 18446744073709551615s left to wait.</p>";
+        let response = Response::parse(page).unwrap();
+        assert_eq!(response.wait, Some(u64::MAX));
+        history.record(Submission { day: 6, ..submission(2, "8", response.verdict, 500, response.wait) });
+        assert_eq!(history.check(5, Part::One, "50", u64::MAX).unwrap_err(), "the server asked to wait indefinitely before submitting again");
+    }
+
+    #[test]
+    fn test_history_file() {
+        let path = std::env::temp_dir().join(format!("aoc-runner-history-{}", std::process::id())).join("submissions.toml");
+        assert_eq!(History::read(&path), Ok(History::default()));
+        let mut history = History::default();
+        history.record(submission(1, "20", Verdict::TooLow, 100, Some(60)));
+        history.record(submission(2, "7", Verdict::Correct, 400, None));
+        history.write(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("[[submission]]\nday = 5\npart = 1\nanswer = \"20\"\nverdict = \"too-low\"\nat = 100\nwait = 60\n"));
+        assert_eq!(History::read(&path), Ok(history));
+        std::fs::write(&path, "[[submission]]\nday = 5").unwrap();
+        assert!(History::read(&path).unwrap_err().starts_with("invalid history"));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let (base_url, requests, server) = serve(vec![
+            "<article><p>That's not the right answer; your answer is too high.</p></article>",
+            "<article><p>That's the right answer!</p></article>",
+        ]);
+        let fetcher = Fetcher::new(base_url, "secret");
+        let mut history = History::default();
+
+        let response = submit(&fetcher, &mut history, 5, Part::Two, "100", 1000).unwrap();
+        assert_eq!(response, Response { verdict: Verdict::TooHigh, wait: None });
+        assert_eq!(requests.recv().unwrap(), ("/2023/day/5/answer".to_string(), "level=2&answer=100".to_string()));
+        // Refused without asking the server.
+        assert_eq!(submit(&fetcher, &mut history, 5, Part::Two, "150", 1010).unwrap_err(), "150 is too high, as 100 was too high already");
+
+        let response = submit(&fetcher, &mut history, 5, Part::Two, "46", 1020).unwrap();
+        assert_eq!(response.verdict, Verdict::Correct);
+        assert_eq!(requests.recv().unwrap().1, "level=2&answer=46");
+        server.join().unwrap();
+        assert_eq!(history.submissions(5, Part::Two).map(|submission| submission.verdict).collect::<Vec<_>>(), [Verdict::TooHigh, Verdict::Correct]);
+    }
+
+    #[test]
+    fn test_submit_without_verdict() {
+        let (base_url, _requests, server) = serve(vec!["<p>Puzzle inputs differ by user.</p>"]);
+        let mut history = History::default();
+        let response = submit(&Fetcher::new(base_url, "secret"), &mut history, 5, Part::One, "46", 1000).unwrap();
+        assert_eq!(response, Response { verdict: Verdict::Unknown, wait: None });
+        assert_eq!(history.submissions(5, Part::One).collect::<Vec<_>>(), [&submission(1, "46", Verdict::Unknown, 1000, None)]);
+        assert_eq!(history.check(5, Part::One, "46", 1001), Ok(()));
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+
+use aoc_common::SolveError;
+
+use crate::{no_digit, Token, Vocabulary};
+
+/// How the calibration value of a line came about, or why it has none.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LineDiagnosis<'a> {
+    /// Counting from 1.
+    pub number: usize,
+    pub line: &'a str,
+    pub calibration: Result<Calibration, SolveError>,
+}
+
+/// The first and last token of a line, and the value they make.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Calibration {
+    pub first: Token,
+    pub last: Token,
+    pub value: usize,
+}
+
+/// Explains the calibration value of every line of `input`, including the lines without any digit.
+pub fn diagnose<'a>(input: &'a str, vocabulary: &Vocabulary) -> Vec<LineDiagnosis<'a>> {
+    return input.lines().enumerate()
+        .map(|(index, line)| {
+            let calibration = vocabulary.find(line.as_bytes())
+                .map(|(first, last)| Calibration { first, last, value: first.digit * 10 + last.digit })
+                .ok_or_else(|| no_digit(line));
+            return LineDiagnosis { number: index + 1, line, calibration };
+        })
+        .collect();
+}
+
+impl Display for LineDiagnosis<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let token = |token: &Token| format!("`{}` at {}..{}", &self.line[token.start..token.end], token.start, token.end);
+        match &self.calibration {
+            Ok(calibration) => write!(
+                f, "line {}: `{}`: first {}, last {}, value {}",
+                self.number, self.line, token(&calibration.first), token(&calibration.last), calibration.value,
+            ),
+            Err(err) => write!(f, "line {}: {err}", self.number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_diagnose {
+    use super::*;
+
+    #[test]
+    fn test_diagnose() {
+        let diagnoses = diagnose("two1nine\nxtwone3\npqrstu\nfünf7", &Vocabulary::english());
+        assert_eq!(diagnoses.iter().map(|diagnosis| diagnosis.to_string()).collect::<Vec<_>>(), [
+            "line 1: `two1nine`: first `two` at 0..3, last `nine` at 4..8, value 29",
+            "line 2: `xtwone3`: first `two` at 1..4, last `3` at 6..7, value 23",
+            "line 3: no answer: there is no digit in `pqrstu`",
+            "line 4: `fünf7`: first `7` at 5..6, last `7` at 5..6, value 77",
+        ]);
+        assert_eq!(diagnoses[1].calibration.as_ref().unwrap().last, Token { digit: 3, start: 6, end: 7 });
+    }
+}
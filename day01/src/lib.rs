@@ -1,53 +1,140 @@
-pub fn process_part1(input: &str) -> usize {
-    input.lines().map(|line| part1(line)).sum()
-}
+use aoc_common::{parallel, ParseError, Solution, SolveError};
+
+pub use diagnose::{diagnose, Calibration, LineDiagnosis};
+pub use vocabulary::{Language, Token, Vocabulary};
+
+mod diagnose;
+pub mod stream;
+mod vocabulary;
+
+/// Environment variable naming the vocabulary part 2 is solved with, see [`Vocabulary::load`].
+pub const VOCABULARY_ENV: &str = "AOC_VOCABULARY";
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    /// The tokens part 2 reads as digits, part 1 only reads the digits themselves.
+    type Params = Vocabulary;
+    type Input<'a> = Vec<&'a str>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Vec<&str>, ParseError> {
+        Ok(input.lines().collect())
+    }
 
-pub fn process_part2(input: &str) -> usize {
-    input.lines().map(|line| part2(line)).sum()
+    fn part1(lines: &Vec<&str>, _: &Vocabulary) -> Result<usize, SolveError> {
+        calibration_sum(lines, &Vocabulary::digits())
+    }
+
+    fn part2(lines: &Vec<&str>, vocabulary: &Vocabulary) -> Result<usize, SolveError> {
+        calibration_sum(lines, vocabulary)
+    }
+
+    /// The vocabulary named by [`VOCABULARY_ENV`], the default one when it is not set.
+    fn params_from_env() -> Result<Vocabulary, String> {
+        return match std::env::var(VOCABULARY_ENV) {
+            Ok(spec) => Vocabulary::load(&spec).map_err(|err| format!("{VOCABULARY_ENV}: {err}")),
+            Err(_) => Ok(Vocabulary::default()),
+        };
+    }
 }
 
-fn part1(input: &str) -> usize {
-    let a = input.chars().find(|c| c.is_numeric()).unwrap();
-    let b = input.chars().rev().find(|c| c.is_numeric()).unwrap();
-    return format!("{a}{b}").parse::<usize>().unwrap();
+pub fn process_part1(input: &str) -> Result<usize, SolveError> {
+    Day01::solve_part1(input)
 }
 
-fn part2(input: &str) -> usize {
-    return part1(replace_digits(input).as_str());
+pub fn process_part2(input: &str) -> Result<usize, SolveError> {
+    Day01::solve_part2(input)
 }
 
+fn calibration_sum(lines: &[&str], vocabulary: &Vocabulary) -> Result<usize, SolveError> {
+    parallel::try_sum(lines, |line| vocabulary.calibration_value(line).ok_or_else(|| no_digit(line)))
+}
 
-fn replace_digits(input: &str) -> String {
-    input.replace("one", "o1e")
-        .replace("two", "t2o")
-        .replace("three", "t3ree")
-        .replace("four", "f4ur")
-        .replace("five", "f5ve")
-        .replace("six", "s6x")
-        .replace("seven", "s7n")
-        .replace("eight", "e8ght")
-        .replace("nine", "n9ne")
+fn no_digit(line: &str) -> SolveError {
+    SolveError::no_answer(format!("there is no digit in `{line}`"))
 }
 
+#[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
 
+    fn part1(line: &str) -> Option<usize> {
+        Vocabulary::digits().calibration_value(line)
+    }
+
+    fn part2(line: &str) -> Option<usize> {
+        Vocabulary::english().calibration_value(line)
+    }
+
+    const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+    /// Finds the digits by checking, at every position, whether a digit or a spelled out digit starts there.
+    fn calibration_value_oracle(line: &str, words: bool) -> usize {
+        let digits: Vec<usize> = (0..line.len())
+            .filter_map(|i| {
+                let rest = &line[i..];
+                let digit = rest.chars().next().unwrap().to_digit(10).map(|digit| digit as usize);
+                let word = WORDS.iter().position(|word| words && rest.starts_with(word)).map(|index| index + 1);
+                return digit.or(word);
+            })
+            .collect();
+        return digits.first().unwrap() * 10 + digits.last().unwrap();
+    }
+
+    /// Lines of letters, digits and spelled out digits, often overlapping, with at least one digit.
+    fn line() -> impl Strategy<Value = String> {
+        let token = prop_oneof![
+            "[a-z]{1,3}",
+            "[1-9]",
+            prop::sample::select(WORDS.to_vec()).prop_map(str::to_string),
+            prop::sample::select(vec!["twone", "oneight", "eightwo", "sevenine", "eighthree", "nineight"]).prop_map(str::to_string),
+        ];
+        return (prop::collection::vec(token, 0..8), "[1-9]", any::<prop::sample::Index>())
+            .prop_map(|(mut tokens, digit, index)| {
+                tokens.insert(index.index(tokens.len() + 1), digit);
+                return tokens.concat();
+            });
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(line in line()) {
+            prop_assert_eq!(part1(&line), Some(calibration_value_oracle(&line, false)));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(line in line()) {
+            prop_assert_eq!(part2(&line), Some(calibration_value_oracle(&line, true)));
+        }
+    }
+
     #[test]
     pub fn test_part1() {
-        assert_eq!(part1("treb7uchet"), 77);
-        assert_eq!(part1("1abc2"), 12);
-        assert_eq!(part1("pqr3stu8vwx"), 38);
-        assert_eq!(part1("a1b2c3d4e5f"), 15);
+        assert_eq!(part1("treb7uchet"), Some(77));
+        assert_eq!(part1("1abc2"), Some(12));
+        assert_eq!(part1("pqr3stu8vwx"), Some(38));
+        assert_eq!(part1("a1b2c3d4e5f"), Some(15));
     }
 
     #[test]
     pub fn test_part2() {
-        assert_eq!(part2("two1nine"), 29);
-        assert_eq!(part2("eightwothree"), 83);
-        assert_eq!(part2("abcone2threexyz"), 13);
-        assert_eq!(part2("xtwone3four"), 24);
-        assert_eq!(part2("4nineeightseven2"), 42);
-        assert_eq!(part2("zoneight234"), 14);
-        assert_eq!(part2("7pqrstsixteen"), 76);
+        assert_eq!(part2("two1nine"), Some(29));
+        assert_eq!(part2("eightwothree"), Some(83));
+        assert_eq!(part2("abcone2threexyz"), Some(13));
+        assert_eq!(part2("xtwone3four"), Some(24));
+        assert_eq!(part2("4nineeightseven2"), Some(42));
+        assert_eq!(part2("zoneight234"), Some(14));
+        assert_eq!(part2("7pqrstsixteen"), Some(76));
+    }
+
+    #[test]
+    fn test_line_without_digit() {
+        assert_eq!(part1("½٣abc"), None);
+        assert_eq!(process_part1("1abc2\npqrstu"), Err(SolveError::no_answer("there is no digit in `pqrstu`")));
+        assert_eq!(process_part2("1abc2\nseven"), Ok(12 + 77));
     }
 }
\ No newline at end of file
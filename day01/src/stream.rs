@@ -0,0 +1,91 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+use aoc_common::{checked, SolveError};
+
+use crate::Vocabulary;
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(std::io::Error),
+    Solve(SolveError),
+}
+
+impl Display for StreamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "could not read input: {err}"),
+            StreamError::Solve(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Sums the calibration values of the lines read from `reader`, one line at a time, so the input is
+/// never held in memory as a whole. Lines need not be UTF-8, the tokens are matched on their bytes.
+pub fn calibration_sum(mut reader: impl BufRead, vocabulary: &Vocabulary) -> Result<usize, StreamError> {
+    let mut line = Vec::new();
+    let mut sum = 0;
+    for number in 1.. {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(StreamError::Io)? == 0 {
+            break;
+        }
+        let (first, last) = vocabulary.find(&line).ok_or_else(|| {
+            let text = String::from_utf8_lossy(&line);
+            let message = format!("there is no digit in line {number}: `{}`", text.trim_end_matches(['\n', '\r']));
+            return StreamError::Solve(SolveError::no_answer(message));
+        })?;
+        sum = checked::add(sum, first.digit * 10 + last.digit).map_err(StreamError::Solve)?;
+    }
+    return Ok(sum);
+}
+
+#[cfg(test)]
+mod test_stream {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+
+    /// Reads the same bytes over and over, like a generated input of any size.
+    struct Cycle<'a>(std::iter::Cycle<std::slice::Iter<'a, u8>>);
+
+    impl Read for Cycle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            for byte in buf.iter_mut() {
+                *byte = *self.0.next().unwrap();
+            }
+            return Ok(buf.len());
+        }
+    }
+
+    #[test]
+    fn test_calibration_sum() {
+        let input = "x2y7eight\nsevenine\r\nfour\n3threeight";
+        assert_eq!(calibration_sum(input.as_bytes(), &Vocabulary::english()).unwrap(), 28 + 79 + 44 + 38);
+        assert_eq!(calibration_sum(&b"1abc2\n\xff7\n"[..], &Vocabulary::digits()).unwrap(), 12 + 77);
+        assert_eq!(calibration_sum("".as_bytes(), &Vocabulary::digits()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_large_input() {
+        let lines = b"xtwone3four\n9\n";
+        let reader = BufReader::new(Cycle(lines.iter().cycle()).take(lines.len() as u64 * 100_000));
+        assert_eq!(calibration_sum(reader, &Vocabulary::english()).unwrap(), (24 + 99) * 100_000);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = calibration_sum("1abc2\nseven\n".as_bytes(), &Vocabulary::digits()).unwrap_err();
+        assert_eq!(err.to_string(), "no answer: there is no digit in line 2: `seven`");
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+        let err = calibration_sum(BufReader::new(Failing), &Vocabulary::digits()).unwrap_err();
+        assert_eq!(err.to_string(), "could not read input: disk on fire");
+    }
+}
@@ -0,0 +1,249 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use aho_corasick::AhoCorasick;
+use serde::Deserialize;
+
+/// The tokens that stand for a digit in a calibration line, like `7` and `seven`.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    tokens: Vec<(String, usize)>,
+    /// Finds all of the tokens at once, reporting them by their index in `tokens`.
+    automaton: AhoCorasick,
+}
+
+/// A token found in a line: the digit it stands for, and the bytes it spans.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Token {
+    pub digit: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The languages with a built-in vocabulary, spelling out `1` to `9`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Language {
+    English,
+    Dutch,
+    German,
+    French,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [Language::English, Language::Dutch, Language::German, Language::French];
+
+    pub fn name(self: Language) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::Dutch => "dutch",
+            Language::German => "german",
+            Language::French => "french",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Language> {
+        return Language::ALL.into_iter().find(|language| language.name() == name);
+    }
+
+    /// The words for `1` to `9`.
+    fn words(self: Language) -> [&'static str; 9] {
+        match self {
+            Language::English => ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"],
+            Language::Dutch => ["een", "twee", "drie", "vier", "vijf", "zes", "zeven", "acht", "negen"],
+            Language::German => ["eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun"],
+            Language::French => ["un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf"],
+        }
+    }
+}
+
+/// A vocabulary as written in TOML, a table of the words and the digits they stand for:
+///
+/// ```toml
+/// [words]
+/// uno = 1
+/// dos = 2
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VocabularyFile {
+    words: BTreeMap<String, usize>,
+}
+
+impl Vocabulary {
+    /// A vocabulary of the given tokens and the digits they stand for. Empty tokens are left out, as they
+    /// would be found everywhere. Fails when a token stands for more than 9, or there are too many tokens,
+    /// or they are too long, to match.
+    pub fn new<S: Into<String>>(tokens: impl IntoIterator<Item = (S, usize)>) -> Result<Vocabulary, String> {
+        let tokens: Vec<(String, usize)> = tokens.into_iter()
+            .map(|(token, digit)| (token.into(), digit))
+            .filter(|(token, _)| !token.is_empty())
+            .collect();
+        if let Some((token, digit)) = tokens.iter().find(|(_, digit)| *digit > 9) {
+            return Err(format!("`{token}` stands for {digit}, which is not a digit"));
+        }
+        let automaton = AhoCorasick::new(tokens.iter().map(|(token, _)| token))
+            .map_err(|err| format!("the words cannot be matched: {err}"))?;
+        return Ok(Vocabulary { tokens, automaton });
+    }
+
+    /// Only the digits `0` to `9` themselves.
+    pub fn digits() -> Vocabulary {
+        return Vocabulary::built_in(std::iter::empty());
+    }
+
+    /// The digits, and `words` standing for a digit as well.
+    pub fn with_words<S: Into<String>>(words: impl IntoIterator<Item = (S, usize)>) -> Result<Vocabulary, String> {
+        let digits = (0..10).map(|digit| (digit.to_string(), digit));
+        return Vocabulary::new(digits.chain(words.into_iter().map(|(word, digit)| (word.into(), digit))));
+    }
+
+    /// The digits, and `1` to `9` spelled out in `language`.
+    pub fn language(language: Language) -> Vocabulary {
+        return Vocabulary::built_in(language.words().into_iter().zip(1..));
+    }
+
+    /// The digits and a few short `words`, which always make a vocabulary.
+    fn built_in(words: impl IntoIterator<Item = (&'static str, usize)>) -> Vocabulary {
+        return Vocabulary::with_words(words).expect("a few short words can be matched");
+    }
+
+    pub fn english() -> Vocabulary {
+        return Vocabulary::language(Language::English);
+    }
+
+    /// The digits, and the words of a [`VocabularyFile`].
+    pub fn from_toml(content: &str) -> Result<Vocabulary, String> {
+        let file: VocabularyFile = toml::from_str(content).map_err(|err| err.to_string())?;
+        if file.words.contains_key("") {
+            return Err("a word cannot be empty".to_string());
+        }
+        return Vocabulary::with_words(file.words);
+    }
+
+    /// The built-in vocabulary of the language named `spec`, or else the one in the TOML file at `spec`.
+    pub fn load(spec: &str) -> Result<Vocabulary, String> {
+        if let Some(language) = Language::from_name(spec) {
+            return Ok(Vocabulary::language(language));
+        }
+        let path = Path::new(spec);
+        let content = std::fs::read_to_string(path).map_err(|err| {
+            let names: Vec<&str> = Language::ALL.iter().map(|language| language.name()).collect();
+            return format!("`{spec}` is neither a language ({}) nor a readable vocabulary file: {err}", names.join(", "));
+        })?;
+        return Vocabulary::from_toml(&content).map_err(|err| format!("invalid vocabulary {}: {err}", path.display()));
+    }
+
+    /// The first and the last token in `line`, the one starting first and the one starting last, which may
+    /// overlap as in `eightwo`, found in a single pass. Of tokens starting at the same position, the
+    /// longest counts.
+    pub fn find(self: &Vocabulary, line: &[u8]) -> Option<(Token, Token)> {
+        let mut found: Option<(Token, Token)> = None;
+        for found_match in self.automaton.find_overlapping_iter(line) {
+            let token = Token {
+                digit: self.tokens[found_match.pattern().as_usize()].1,
+                start: found_match.start(),
+                end: found_match.end(),
+            };
+            let earlier = |other: &Token| (token.start, Reverse(token.end)) < (other.start, Reverse(other.end));
+            let later = |other: &Token| (token.start, token.end) > (other.start, other.end);
+            found = match found {
+                None => Some((token, token)),
+                Some((first, last)) => Some((if earlier(&first) { token } else { first }, if later(&last) { token } else { last })),
+            };
+        }
+        return found;
+    }
+
+    /// The digit of the first token in `line`. Tokens may overlap, so `eightwo` starts with an 8.
+    pub fn first(self: &Vocabulary, line: &str) -> Option<usize> {
+        return self.find(line.as_bytes()).map(|(first, _)| first.digit);
+    }
+
+    /// The digit of the last token in `line`. `eightwo` ends with a 2.
+    pub fn last(self: &Vocabulary, line: &str) -> Option<usize> {
+        return self.find(line.as_bytes()).map(|(_, last)| last.digit);
+    }
+
+    /// The first and last digit of `line` as a two-digit number, `None` without digits.
+    pub fn calibration_value(self: &Vocabulary, line: &str) -> Option<usize> {
+        return self.find(line.as_bytes()).map(|(first, last)| first.digit * 10 + last.digit);
+    }
+}
+
+/// The automaton follows from the tokens.
+impl PartialEq for Vocabulary {
+    fn eq(&self, other: &Vocabulary) -> bool {
+        self.tokens == other.tokens
+    }
+}
+
+impl Eq for Vocabulary {}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Vocabulary::english()
+    }
+}
+
+#[cfg(test)]
+mod test_vocabulary {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_tokens() {
+        let english = Vocabulary::english();
+        assert_eq!(english.first("eightwo"), Some(8));
+        assert_eq!(english.last("eightwo"), Some(2));
+        assert_eq!(english.calibration_value("xtwone"), Some(21));
+        assert_eq!(english.calibration_value("nineight"), Some(98));
+        assert_eq!(english.calibration_value("onè"), None);
+        assert_eq!(Vocabulary::digits().calibration_value("one2three"), Some(22));
+    }
+
+    #[test]
+    fn test_custom_tokens() {
+        let vocabulary = Vocabulary::new([("", 0), ("a", 1), ("ab", 2), ("é", 3)]).unwrap();
+        // The longest token starting at a position wins.
+        assert_eq!(vocabulary.first("xaby"), Some(2));
+        assert_eq!(vocabulary.last("abéa"), Some(1));
+        assert_eq!(vocabulary.calibration_value("éxab"), Some(32));
+        assert_eq!(vocabulary.calibration_value("xyz"), None);
+        assert_eq!(Vocabulary::new([("a", 1), ("b", 10)]).unwrap_err(), "`b` stands for 10, which is not a digit");
+        assert_eq!(Vocabulary::with_words([("max", usize::MAX)]).unwrap_err(), format!("`max` stands for {}, which is not a digit", usize::MAX));
+    }
+
+    #[test]
+    fn test_languages() {
+        assert_eq!(Vocabulary::language(Language::Dutch).calibration_value("tweeacht3zeventien"), Some(27));
+        assert_eq!(Vocabulary::language(Language::German).calibration_value("xfünfsechsiebenull"), Some(57));
+        assert_eq!(Vocabulary::language(Language::French).calibration_value("huitroisept"), Some(87));
+        assert_eq!(Vocabulary::language(Language::French).calibration_value("onetwo"), None);
+        for language in Language::ALL {
+            assert_eq!(Language::from_name(language.name()), Some(language));
+        }
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let vocabulary = Vocabulary::from_toml("[words]\nuno = 1\ndos = 2\nnueve = 9\n").unwrap();
+        assert_eq!(vocabulary.calibration_value("adosxunox"), Some(21));
+        assert_eq!(vocabulary.calibration_value("nueve"), Some(99));
+        assert_eq!(Vocabulary::from_toml("[words]\ndiez = 10").unwrap_err(), "`diez` stands for 10, which is not a digit");
+        assert_eq!(Vocabulary::from_toml("[words]\n\"\" = 1").unwrap_err(), "a word cannot be empty");
+        assert!(Vocabulary::from_toml("[words]\nuno = \"1\"").is_err());
+        assert!(Vocabulary::from_toml("[word]\nuno = 1").is_err());
+    }
+
+    #[test]
+    fn test_load() {
+        assert_eq!(Vocabulary::load("german"), Ok(Vocabulary::language(Language::German)));
+        let path = std::env::temp_dir().join(format!("day01-vocabulary-{}.toml", std::process::id()));
+        std::fs::write(&path, "[words]\nuno = 1\n").unwrap();
+        assert_eq!(Vocabulary::load(path.to_str().unwrap()), Vocabulary::with_words([("uno", 1)]));
+        std::fs::write(&path, "[words]\nuno = 11\n").unwrap();
+        assert!(Vocabulary::load(path.to_str().unwrap()).unwrap_err().starts_with("invalid vocabulary"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(Vocabulary::load("klingon").unwrap_err().starts_with("`klingon` is neither a language (english, dutch, german, french)"));
+    }
+}
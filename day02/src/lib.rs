@@ -1,127 +1,352 @@
 use std::cmp::max;
 use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
 use regex::Regex;
 
-pub fn part1(input: &str) -> usize {
-    let cubes_in_bag = BTreeMap::from([
-        (Color::Red, 12),
-        (Color::Green, 13),
-        (Color::Blue, 14),
-    ]);
-
-    return input
-        .lines()
-        .map(Game::from_str)
-        .filter(|game| game.is_valid(&cubes_in_bag))
-        .map(|game| game.id)
-        .sum();
-}
+use aoc_common::parse::{parse_lines, parse_number};
+use aoc_common::{checked, ParseError, Solution, SolveError};
+
+/// Environment variable holding the bag part 1 checks the games against, like
+/// `12 red, 13 green, 14 blue`, see [`Bag::from_str`].
+pub const BAG_ENV: &str = "AOC_BAG";
+
+pub struct Day02;
 
-pub fn part2(input: &str) -> usize {
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    /// The bag part 1 checks the games against. Part 2 does without, a game's power only depends on its rounds.
+    type Params = Bag;
+    type Input<'a> = Vec<Game<'a>>;
+    type Answer = usize;
 
-    return input
-        .lines()
-        .map(Game::from_str)
-        .map(|game| {
-            let cubes_in_bag: BTreeMap<Color, usize> = BTreeMap::from([]);
+    fn parse(input: &str) -> Result<Vec<Game<'_>>, ParseError> {
+        parse_lines(input, Game::from_str)
+    }
+
+    fn part1(games: &Vec<Game>, bag: &Bag) -> Result<usize, SolveError> {
+        return checked::sum(games
+            .iter()
+            .filter(|game| game.is_valid(bag))
+            .map(|game| game.id));
+    }
 
-            let result = game.rounds.iter()
-                .fold(cubes_in_bag, |mut acc, round| {
-                    round.iter().for_each(|observation| {
-                        acc.entry(observation.color)
-                            .and_modify(|amount| { *amount = max(*amount, observation.amount) })
-                            .or_insert(observation.amount);
+    fn part2(games: &Vec<Game>, _: &Bag) -> Result<usize, SolveError> {
+        let powers = games
+            .iter()
+            .map(|game| {
+                // The fewest cubes of every colour the game shows, colours it never shows do not count.
+                let cubes_in_bag: BTreeMap<&str, usize> = BTreeMap::new();
+
+                let result = game.rounds.iter()
+                    .fold(cubes_in_bag, |mut acc, round| {
+                        round.iter().for_each(|observation| {
+                            let amount = acc.entry(observation.color).or_insert(0);
+                            *amount = max(*amount, observation.amount);
+                        });
+                        return acc;
                     });
-                    return acc;
-                });
-            return result.values().product::<usize>();
-        })
-        .sum();
+                return checked::product(result.values().copied());
+            })
+            .collect::<Result<Vec<usize>, SolveError>>()?;
+        return checked::sum(powers);
+    }
+
+    /// The bag in [`BAG_ENV`], the default one when it is not set.
+    fn params_from_env() -> Result<Bag, String> {
+        return match std::env::var(BAG_ENV) {
+            Ok(bag) => Bag::from_str(&bag).map_err(|err| format!("{BAG_ENV}: {}", err.render(&bag))),
+            Err(_) => Ok(Bag::default()),
+        };
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day02::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day02::solve_part2(input)
+}
+
+/// How many cubes of each colour are in the bag. Colours missing from the bag have no cubes in it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Bag {
+    cubes: BTreeMap<String, usize>,
+}
+
+impl Bag {
+    pub fn new<S: Into<String>>(cubes: impl IntoIterator<Item = (S, usize)>) -> Bag {
+        Bag { cubes: cubes.into_iter().map(|(color, amount)| (color.into(), amount)).collect() }
+    }
+
+    /// Parses the contents of a bag written like a round, as in `12 red, 13 green, 14 blue`.
+    pub fn from_str(input: &str) -> Result<Bag, ParseError> {
+        let mut cubes = BTreeMap::new();
+        for cubes_of_color in input.split(',') {
+            let observation = CubeObservation::from_str(cubes_of_color).map_err(|err| err.within(input, cubes_of_color))?;
+            if cubes.insert(observation.color.to_string(), observation.amount).is_some() {
+                return Err(ParseError::locate(input, observation.color, "colour already in the bag"));
+            }
+        }
+        return Ok(Bag { cubes });
+    }
+
+    pub fn cubes(self: &Bag, color: &str) -> usize {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Bag::new([("red", 12), ("green", 13), ("blue", 14)])
+    }
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game<'a> {
     id: usize,
-    rounds: Vec<Vec<CubeObservation>>,
+    rounds: Vec<Vec<CubeObservation<'a>>>,
 }
 
-impl Game {
-    pub fn is_valid(&self, cubes: &BTreeMap<Color, usize> ) -> bool {
-        self.rounds.iter().all(|round| {
-            round.iter().all(|observation| {
-                cubes[&observation.color] >= observation.amount
-            })
-        })
+/// Why a game cannot have been played with a bag: a round showing more cubes of a colour than the bag holds.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Impossible {
+    pub game: usize,
+    /// Counting from 1.
+    pub round: usize,
+    pub color: String,
+    pub shown: usize,
+    pub in_bag: usize,
+}
+
+impl Display for Impossible {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "game {} is impossible: round {} shows {} {}, ", self.game, self.round, self.shown, self.color)?;
+        match self.in_bag {
+            0 => write!(f, "but there are no {} cubes in the bag", self.color),
+            in_bag => write!(f, "but there are only {in_bag} in the bag"),
+        }
+    }
+}
+
+impl<'a> Game<'a> {
+    pub fn id(self: &Game<'a>) -> usize {
+        self.id
+    }
+
+    pub fn is_valid(&self, bag: &Bag) -> bool {
+        self.check(bag).is_ok()
     }
 
-    pub fn from_str(input: &str) -> Game {
-        let game_id_regex = Regex::new(r"^Game (?<game_id>\d+):(?<rounds>.*)").expect("Invalid game match regex");
+    /// Fails with the first round, and the first colour in it, that shows more cubes than `bag` holds.
+    pub fn check(self: &Game<'a>, bag: &Bag) -> Result<(), Impossible> {
+        for (index, round) in self.rounds.iter().enumerate() {
+            if let Some(observation) = round.iter().find(|observation| observation.amount > bag.cubes(observation.color)) {
+                return Err(Impossible {
+                    game: self.id,
+                    round: index + 1,
+                    color: observation.color.to_string(),
+                    shown: observation.amount,
+                    in_bag: bag.cubes(observation.color),
+                });
+            }
+        }
+        return Ok(());
+    }
 
-        let captures = game_id_regex.captures(input).expect("Invalid input");
-        let game_id = captures["game_id"].parse::<usize>().unwrap();
-        let rounds = &captures["rounds"];
+    pub fn from_str(input: &'a str) -> Result<Game<'a>, ParseError> {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        let game_id_regex = REGEX.get_or_init(|| Regex::new(r"^Game (?<game_id>\d+):(?<rounds>.*)").expect("Invalid game match regex"));
+
+        let captures = game_id_regex.captures(input)
+            .ok_or_else(|| ParseError::locate(input, input, "expected `Game <id>: <rounds>`"))?;
+        let game_id = parse_number(input, captures.name("game_id").unwrap().as_str())?;
+        let rounds = captures.name("rounds").unwrap().as_str();
         let rounds = rounds
             .split(';')
             .map(|round| round
                 .split(',')
-                .map(|observation| CubeObservation::from_str(observation))
-                .collect())
-            .collect();
+                .map(|observation| CubeObservation::from_str(observation).map_err(|err| err.within(input, observation)))
+                .collect::<Result<Vec<CubeObservation>, ParseError>>())
+            .collect::<Result<Vec<Vec<CubeObservation>>, ParseError>>()?;
 
-        return Game {
+        return Ok(Game {
             id: game_id,
             rounds,
-        };
+        });
     }
 }
 
-#[derive(Debug)]
-struct CubeObservation {
-    color: Color,
-    amount: usize,
-}
-
-impl CubeObservation {
-    pub fn from_str(input: &str) -> CubeObservation {
-        let [amount, color] = input.trim().split(' ').collect::<Vec<&str>>()[..] else { panic!() };
-        return CubeObservation {
-            color: Color::from_str(color),
-            amount: amount.parse::<usize>().unwrap(),
-        };
-    }
+/// Checks every game against `bag`, reporting the games that are impossible and why.
+pub fn impossible_games(input: &str, bag: &Bag) -> Result<Vec<Impossible>, ParseError> {
+    let games = Day02::parse(input)?;
+    return Ok(games.iter().filter_map(|game| game.check(bag).err()).collect());
 }
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
-enum Color {
-    Red,
-    Green,
-    Blue,
+#[derive(Debug)]
+pub struct CubeObservation<'a> {
+    /// Any name made of letters.
+    color: &'a str,
+    amount: usize,
 }
 
-impl Color {
-    pub fn from_str(input: &str) -> Color {
-        return match input {
-            "red" => Color::Red,
-            "green" => Color::Green,
-            "blue" => Color::Blue,
-            _ => panic!("Invalid color"),
+impl<'a> CubeObservation<'a> {
+    pub fn from_str(input: &'a str) -> Result<CubeObservation<'a>, ParseError> {
+        let observation = input.trim();
+        let [amount, color] = observation.split(' ').collect::<Vec<&str>>()[..] else {
+            return Err(ParseError::locate(input, observation, "expected `<amount> <color>`"));
         };
+        if color.is_empty() || !color.chars().all(char::is_alphabetic) {
+            return Err(ParseError::locate(input, color, "invalid color"));
+        }
+        return Ok(CubeObservation {
+            color,
+            amount: parse_number(input, amount)?,
+        });
     }
 }
 
 #[cfg(test)]
 mod test_day02 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    const COLORS: [&str; 3] = ["red", "green", "blue"];
+
+    /// Games as the rounds they consist of, every round showing some of the colours in any order.
+    fn games() -> impl Strategy<Value = Vec<Vec<Vec<(usize, &'static str)>>>> {
+        let round = prop::sample::subsequence(COLORS.to_vec(), 1..=3)
+            .prop_flat_map(|colors| {
+                let amounts = prop::collection::vec(1..=20usize, colors.len());
+                (amounts, Just(colors)).prop_map(|(amounts, colors)| amounts.into_iter().zip(colors).collect::<Vec<_>>())
+            })
+            .prop_shuffle();
+        return prop::collection::vec(prop::collection::vec(round, 1..5), 1..6);
+    }
+
+    fn render(games: &[Vec<Vec<(usize, &str)>>]) -> String {
+        return games.iter().enumerate()
+            .map(|(index, rounds)| {
+                let rounds = rounds.iter()
+                    .map(|round| round.iter().map(|(amount, color)| format!("{amount} {color}")).collect::<Vec<_>>().join(", "))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                return format!("Game {}: {rounds}", index + 1);
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    /// The most cubes of a colour shown in any round, 0 when it's never shown.
+    fn most_shown(rounds: &[Vec<(usize, &str)>], color: &str) -> usize {
+        return rounds.iter().flatten()
+            .filter(|(_, shown)| *shown == color)
+            .map(|(amount, _)| *amount)
+            .max()
+            .unwrap_or(0);
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(games in games()) {
+            let expected: usize = games.iter().enumerate()
+                .filter(|(_, rounds)| most_shown(rounds, "red") <= 12 && most_shown(rounds, "green") <= 13 && most_shown(rounds, "blue") <= 14)
+                .map(|(index, _)| index + 1)
+                .sum();
+            prop_assert_eq!(part1(&render(&games)), Ok(expected));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(games in games()) {
+            let expected: usize = games.iter()
+                .map(|rounds| COLORS.iter().map(|color| most_shown(rounds, color)).filter(|most| *most > 0).product::<usize>())
+                .sum();
+            prop_assert_eq!(part2(&render(&games)), Ok(expected));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part_1() {
-        let example_input = include_str!("../resources/example.txt");
-        assert_eq!(part1(example_input), 8);
+        let example_input = &example!("example.txt");
+        assert_eq!(part1(example_input), Ok(8));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part_2() {
-        let example_input = include_str!("../resources/example.txt");
-        assert_eq!(part2(example_input), 2286);
+        let example_input = &example!("example.txt");
+        assert_eq!(part2(example_input), Ok(2286));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day02::parse("Game 1: 3 blue, 4 red\nGame 2: 1 blue, 2 purp1e").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 19, "purp1e", "invalid color"));
+        let err = Day02::parse("Game 1: 3 blue, red").unwrap_err();
+        assert_eq!((err.line, err.column, err.message.as_str()), (1, 17, "expected `<amount> <color>`"));
+    }
+
+    #[test]
+    fn test_other_colors() {
+        let input = "Game 1: 3 blue, 4 purple; 2 green\nGame 2: 1 blue; 9 purple, 2 red\nGame 3: 1 red, 1 green, 1 blue";
+        let bag = Bag::from_str("5 purple, 1 blue, 2 red").unwrap();
+        let games = Day02::parse(input).unwrap();
+        assert_eq!(Day02::part1(&games, &bag), Ok(0));
+        assert_eq!(Day02::part1(&games, &Bag::new([("purple", 9), ("blue", 3), ("green", 2), ("red", 2)])), Ok(6));
+        // The power only depends on the colours a game shows, whatever the bag holds.
+        for bag in [Bag::default(), bag, Bag::new([("purple", 0)]), Bag::new([("gold", 5)])] {
+            assert_eq!(Day02::part2(&games, &bag), Ok(3 * 4 * 2 + 9 * 2 + 1));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
+    fn test_check_reports_round_and_color() {
+        let input = &example!("example.txt");
+        let impossible = impossible_games(input, &Bag::default()).unwrap();
+        assert_eq!(impossible.iter().map(|game| game.to_string()).collect::<Vec<_>>(), [
+            "game 3 is impossible: round 1 shows 20 red, but there are only 12 in the bag",
+            "game 4 is impossible: round 3 shows 15 blue, but there are only 14 in the bag",
+        ]);
+    }
+
+    #[test]
+    fn test_check() {
+        let input = "Game 1: 3 red, 14 blue; 2 green\nGame 2: 1 blue; 13 red\nGame 3: 12 red, 1 gold";
+        let impossible = impossible_games(input, &Bag::default()).unwrap();
+        assert_eq!(impossible.iter().map(|game| game.to_string()).collect::<Vec<_>>(), [
+            "game 2 is impossible: round 2 shows 13 red, but there are only 12 in the bag",
+            "game 3 is impossible: round 1 shows 1 gold, but there are no gold cubes in the bag",
+        ]);
+        assert!(impossible_games(input, &Bag::from_str("13 red, 14 blue, 2 green, 1 gold").unwrap()).unwrap().is_empty());
+        let game = Game::from_str("Game 7: 1 red; 2 blue, 1 gold").unwrap();
+        let err = game.check(&Bag::from_str("1 red, 2 blue").unwrap()).unwrap_err();
+        assert_eq!(err, Impossible { game: 7, round: 2, color: "gold".to_string(), shown: 1, in_bag: 0 });
+        assert_eq!(err.to_string(), "game 7 is impossible: round 2 shows 1 gold, but there are no gold cubes in the bag");
+        assert_eq!(game.check(&Bag::from_str("1 red, 2 blue, 1 gold").unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn test_bag_from_str() {
+        assert_eq!(Bag::from_str("12 red, 13 green, 14 blue"), Ok(Bag::default()));
+        assert_eq!(Bag::from_str("1 red, 2 red").unwrap_err(), ParseError::new(1, 10, "red", "colour already in the bag"));
+        assert_eq!(Bag::from_str("1 red, blue").unwrap_err().message, "expected `<amount> <color>`");
+        assert_eq!(Bag::from_str("").unwrap_err().message, "expected `<amount> <color>`");
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = format!("Game {}: 1 red\nGame 1: 2 blue", usize::MAX);
+        assert_eq!(part1(&input), Err(checked::overflow::<usize>()));
+        assert_eq!(part2(&format!("Game 1: {} red, 2 blue, 1 green", usize::MAX)), Err(checked::overflow::<usize>()));
+        // Every power fits, but their sum does not.
+        let game = format!("Game 1: {} red", usize::MAX / 2 + 1);
+        assert_eq!(part2(&game), Ok(usize::MAX / 2 + 1));
+        assert_eq!(part2(&format!("{game}\n{game}")), Err(checked::overflow::<usize>()));
     }
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
-use day02::{part1, part2};
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../resources/input.txt");
-    println!("Part1: {}", part1(input));
-    println!("Part1: {}", part2(input));
+use aoc_common::inputs;
+use day02::Day02;
+
+fn main() -> ExitCode {
+    aoc_common::run::<Day02>(inputs!())
 }
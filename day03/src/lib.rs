@@ -1,88 +1,89 @@
 use std::collections::{BTreeMap, HashSet};
 
-pub fn part1(input: &str) -> u32 {
-    let sparse_matrix = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-           line
-               .chars()
-               .enumerate()
-               .filter(|(_, character)| *character != '.' )
-               .map(move |(x, character)| (Point(y as i32, x as i32), Cell::from_char(character)))
-        })
-        .collect::<BTreeMap<Point, Cell>>();
-
-    let numbers = construct_numbers(&sparse_matrix);
-    let symbol_set: HashSet<Point> = sparse_matrix.iter().filter_map(|(point, cell)| match cell {
-        Cell::Symbol(_) => Some(point.clone()),
-        _ => None,
-    }).collect();
-
-    return numbers.iter().filter(|number| {
-        number.neighbouring_points().iter().any(|neighbour| symbol_set.contains(neighbour))
-    }).map(|number| number.value).sum();
+use aoc_common::{checked, ParseError, Solution, SolveError};
+use aoc_grid::{Point, SparseGrid};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    type Params = ();
+    type Input<'a> = SparseGrid<Cell>;
+    type Answer = u32;
+
+    fn parse(input: &str) -> Result<SparseGrid<Cell>, ParseError> {
+        SparseGrid::parse(input, |character| Ok::<_, &str>(Cell::from_char(character)))
+    }
+
+    fn part1(sparse_matrix: &SparseGrid<Cell>, _: &()) -> Result<u32, SolveError> {
+        let numbers = construct_numbers(sparse_matrix)?;
+        return checked::sum(numbers.iter().filter(|number| {
+            number.neighbouring_points().iter().any(|neighbour| matches!(sparse_matrix.get(*neighbour), Some(Cell::Symbol(_))))
+        }).map(|number| number.value));
+    }
+
+    fn part2(sparse_matrix: &SparseGrid<Cell>, _: &()) -> Result<u32, SolveError> {
+        // Every location of a number points at the number's first location, so two numbers with the same
+        // value next to one gear are still told apart.
+        let numbers: BTreeMap<Point, (Point, u32)> = construct_numbers(sparse_matrix)?.iter()
+            .flat_map(|number| {
+                number.locations.iter().map(|p| (*p, (number.locations[0], number.value))).collect::<Vec<(Point, (Point, u32))>>()
+            })
+            .collect();
+        let gears: Vec<Point> = sparse_matrix.iter()
+            .filter_map(|(point, cell)| match cell {
+                Cell::Symbol('*') => Some(point),
+                _ => None,
+            })
+            .collect();
+
+        let ratios = gears.iter()
+            .filter_map(|gear| {
+                let neighbours: HashSet<(Point, u32)> = gear.neighbours_8().iter().filter_map(|neighbour| numbers.get(neighbour)).copied().collect();
+                if neighbours.len() != 2 {
+                    return None
+                }
+                return Some(checked::product(neighbours.iter().map(|(_, value)| *value)))
+            })
+            .collect::<Result<Vec<u32>, SolveError>>()?;
+        return checked::sum(ratios);
+    }
 }
 
-pub fn part2(input: &str) -> u32 {
-    let sparse_matrix = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line
-                .chars()
-                .enumerate()
-                .filter(|(_, character)| *character != '.' )
-                .map(move |(x, character)| (Point(y as i32, x as i32), Cell::from_char(character)))
-        })
-        .collect::<BTreeMap<Point, Cell>>();
-
-    let numbers: BTreeMap<Point, u32> = construct_numbers(&sparse_matrix).iter()
-        .flat_map(|number| {
-            number.locations.iter().map(|p| (p.clone(), number.value)).collect::<Vec<(Point, u32)>>()
-        })
-        .collect();
-    let gears: Vec<Point> = sparse_matrix.iter()
-        .filter_map(|(point, cell)| match cell {
-            Cell::Symbol('*') => Some(point.clone()),
-            _ => None,
-        })
-        .collect();
-
-    return gears.iter()
-        .filter_map(|gear| {
-            let neighbours: HashSet<u32> = gear.neighbouring_points().iter().filter_map(|neighbour| numbers.get(neighbour)).map(|x| *x).collect();
-            if neighbours.len() != 2 {
-                return None
-            }
-            return Some(neighbours.iter().product::<u32>())
-        }).sum();
+pub fn part1(input: &str) -> Result<u32, SolveError> {
+    Day03::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<u32, SolveError> {
+    Day03::solve_part2(input)
 }
 
 
 // The not so pretty number constructing code.
 // Preferably would require less in for loop state mutability.
 // This is the best for now. At least it works™️.
-fn construct_numbers(sparse_matrix: &BTreeMap<Point, Cell>) -> Vec<Number> {
+fn construct_numbers(sparse_matrix: &SparseGrid<Cell>) -> Result<Vec<Number>, SolveError> {
     let mut numbers: Vec<Number> = vec![];
     let mut current_number:u32 = 0;
     let mut current_points: Vec<Point> = vec![];
     for (point, cell) in sparse_matrix.iter() {
         if let Cell::Digit(digit) = cell {
-            if current_points.last().is_some_and(|last_point| { last_point.1 + 1 == point.1}) {
-                current_number = current_number * 10 + digit;
-                current_points.push(point.clone());
+            if current_points.last().is_some_and(|last_point| { last_point.y == point.y && last_point.x + 1 == point.x}) {
+                current_number = checked::add(checked::mul(current_number, 10)?, *digit)?;
+                current_points.push(point);
             } else {
-                if current_number > 0 {
+                if !current_points.is_empty() {
                     numbers.push(Number { value: current_number, locations: current_points.clone() });
                 }
                 current_number = *digit;
-                current_points = vec![point.clone()];
+                current_points = vec![point];
             }
         }
     }
-    numbers.push(Number { value: current_number, locations: current_points.clone() });
-    return numbers;
+    if !current_points.is_empty() {
+        numbers.push(Number { value: current_number, locations: current_points });
+    }
+    return Ok(numbers);
 }
 
 #[derive(Debug)]
@@ -93,92 +94,168 @@ struct Number<> {
 
 impl Number {
     pub fn neighbouring_points(self: &Number) -> Vec<Point> {
-        let Point(base_y, start_x) = *self.locations.first().unwrap();
-        let end_x = self.locations.last().unwrap().1;
-        return [Point(base_y, start_x - 1), Point(base_y, end_x + 1)]
+        let Point { x: start_x, y: base_y } = *self.locations.first().unwrap();
+        let end_x = self.locations.last().unwrap().x;
+        return [Point::new(start_x - 1, base_y), Point::new(end_x + 1, base_y)]
             .into_iter()
             .chain(
-                (start_x-1..=end_x+1).into_iter().map(|x| Point(base_y - 1, x))
+                (start_x-1..=end_x+1).map(|x| Point::new(x, base_y - 1))
             )
             .chain(
-                (start_x-1..=end_x+1).into_iter().map(|x| Point(base_y + 1, x))
+                (start_x-1..=end_x+1).map(|x| Point::new(x, base_y + 1))
             ).collect();
     }
 }
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash)]
-struct Point(i32, i32);
-
-impl Point {
-    pub fn neighbouring_points(self: &Point) -> Vec<Point> {
-        return vec![
-            Point(self.0 - 1, self.1 - 1),
-            Point(self.0 - 1, self.1),
-            Point(self.0 - 1, self.1 + 1),
-            Point(self.0, self.1 - 1),
-            Point(self.0, self.1 + 1),
-            Point(self.0 + 1, self.1 - 1),
-            Point(self.0 + 1, self.1),
-            Point(self.0 + 1, self.1 + 1),
-        ]
-    }
-}
-
 #[derive(Debug)]
-enum Cell {
-    None,
+pub enum Cell {
     Symbol(char),
     Digit(u32),
 }
 
 impl Cell {
-    pub fn from_char(content: char) -> Cell {
-        match content {
-            '.' => Cell::None,
-            c if c.is_numeric() => Cell::Digit(c.to_digit(10).expect("Expected digit")),
-            c => Cell::Symbol(c),
-        }
+    /// The cell a character of the schematic stands for, `None` for the empty `.`.
+    pub fn from_char(content: char) -> Option<Cell> {
+        return match (content, content.to_digit(10)) {
+            ('.', _) => None,
+            (_, Some(digit)) => Some(Cell::Digit(digit)),
+            (c, None) => Some(Cell::Symbol(c)),
+        };
     }
 }
 
 #[cfg(test)]
 mod test_day03 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    /// Schematics built from numbers, dots and symbols, with a dot after every number so the numbers
+    /// stay small enough to sum and multiply.
+    fn schematic() -> impl Strategy<Value = String> {
+        let token = prop_oneof![
+            3 => Just(".".to_string()),
+            2 => (1..1000u32).prop_map(|number| format!("{number}.")),
+            1 => prop::sample::select(vec!["*", "#", "+", "$", "/"]).prop_map(str::to_string),
+        ];
+        let row = prop::collection::vec(token, 1..8).prop_map(|tokens| tokens.concat());
+        return prop::collection::vec(row, 1..8).prop_map(|rows| {
+            let width = rows.iter().map(|row| row.len()).max().unwrap();
+            return rows.iter().map(|row| format!("{row:.<width$}")).collect::<Vec<_>>().join("\n");
+        });
+    }
+
+    /// Every number of the schematic as its row, first and last column and value, read straight from the text.
+    fn numbers_oracle(grid: &[Vec<char>]) -> Vec<(usize, usize, usize, u32)> {
+        let mut numbers = vec![];
+        for (y, row) in grid.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let length = row[x..].iter().take_while(|c| c.is_ascii_digit()).count();
+                if length > 0 {
+                    let value = row[x..x + length].iter().collect::<String>().parse().unwrap();
+                    numbers.push((y, x, x + length - 1, value));
+                }
+                x += length.max(1);
+            }
+        }
+        return numbers;
+    }
+
+    fn is_adjacent((y, start, end, _): (usize, usize, usize, u32), (cell_y, cell_x): (usize, usize)) -> bool {
+        return cell_y + 1 >= y && cell_y <= y + 1 && cell_x + 1 >= start && cell_x <= end + 1;
+    }
+
+    fn symbols(grid: &[Vec<char>]) -> Vec<(usize, usize, char)> {
+        return grid.iter().enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (y, x, *c)))
+            .filter(|(_, _, c)| *c != '.' && !c.is_ascii_digit())
+            .collect();
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(input in schematic()) {
+            let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+            let symbols = symbols(&grid);
+            let expected: u32 = numbers_oracle(&grid).into_iter()
+                .filter(|number| symbols.iter().any(|(y, x, _)| is_adjacent(*number, (*y, *x))))
+                .map(|(_, _, _, value)| value)
+                .sum();
+            prop_assert_eq!(part1(&input), Ok(expected));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(input in schematic()) {
+            let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+            let numbers = numbers_oracle(&grid);
+            let expected: u32 = symbols(&grid).into_iter()
+                .filter(|(_, _, c)| *c == '*')
+                .map(|(y, x, _)| numbers.iter().filter(|number| is_adjacent(**number, (y, x))).collect::<Vec<_>>())
+                .filter(|adjacent| adjacent.len() == 2)
+                .map(|adjacent| adjacent[0].3 * adjacent[1].3)
+                .sum();
+            prop_assert_eq!(part2(&input), Ok(expected));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     pub fn test_part1() {
-        let example_input = include_str!("../resources/example.txt");
-        assert_eq!(part1(example_input), 4361);
+        let example_input = &example!("example.txt");
+        assert_eq!(part1(example_input), Ok(4361));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     pub fn test_part2() {
-        let example_input = include_str!("../resources/example.txt");
-        assert_eq!(part2(example_input), 467835);
+        let example_input = &example!("example.txt");
+        assert_eq!(part2(example_input), Ok(467835));
     }
 
     #[test]
     fn test_construct_numbers() {
-        let sparse_matrix = BTreeMap::from([
-            (Point(0, 0), Cell::Digit(4)),
-            (Point(0, 1), Cell::Digit(6)),
-            (Point(0, 2), Cell::Digit(7)),
-            (Point(0, 3), Cell::Symbol('&')),
-            (Point(0, 4), Cell::Digit(1)),
-            (Point(0, 5), Cell::Digit(5)),
-            (Point(1, 0), Cell::Digit(5)),
+        let sparse_matrix = SparseGrid::from_iter([
+            (Point::new(0, 0), Cell::Digit(4)),
+            (Point::new(1, 0), Cell::Digit(6)),
+            (Point::new(2, 0), Cell::Digit(7)),
+            (Point::new(3, 0), Cell::Symbol('&')),
+            (Point::new(4, 0), Cell::Digit(1)),
+            (Point::new(5, 0), Cell::Digit(5)),
+            (Point::new(0, 1), Cell::Digit(5)),
         ]);
 
-        let numbers = construct_numbers(&sparse_matrix);
+        let numbers = construct_numbers(&sparse_matrix).unwrap();
         assert_eq!(numbers[0].value, 467);
         assert_eq!(numbers[1].value, 15);
         assert_eq!(numbers[2].value, 5);
     }
 
+    #[test]
+    fn test_construct_numbers_across_rows() {
+        let sparse_matrix = SparseGrid::from_iter([
+            (Point::new(2, 0), Cell::Digit(1)),
+            (Point::new(3, 0), Cell::Digit(2)),
+            (Point::new(4, 1), Cell::Digit(3)),
+        ]);
+
+        let numbers = construct_numbers(&sparse_matrix).unwrap();
+        assert_eq!(numbers.iter().map(|number| number.value).collect::<Vec<u32>>(), [12, 3]);
+        assert!(construct_numbers(&SparseGrid::from_iter([])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(part1("12345678901*"), Err(checked::overflow::<u32>()));
+        assert_eq!(part2("99999*99999"), Err(checked::overflow::<u32>()));
+    }
+
     #[test]
     fn test_neighbours_of_number() {
         let number = Number {
             value: 467,
-            locations: vec![Point(0, 0), Point(0, 1), Point(0, 2)],
+            locations: vec![Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)],
         };
         assert_eq!(number.neighbouring_points().len(), 12);
     }
@@ -1,7 +1,8 @@
-use day03::{part1, part2};
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../resources/input.txt");
-    println!("Part 1: {}", part1(input));
-    println!("Part 2: {}", part2(input));
+use aoc_common::inputs;
+use day03::Day03;
+
+fn main() -> ExitCode {
+    aoc_common::run::<Day03>(inputs!())
 }
@@ -1,75 +1,182 @@
-use std::collections::VecDeque;
-use regex::Regex;
-
-pub fn part1(input: &str) -> usize {
-    return input.lines().map(|line| Card::from_str(line)).map(|card| card.score()).sum();
-}
-
-pub fn part2(input: &str) -> usize {
-    let mut cards: VecDeque<(usize, Card)> = input.lines().map(|line| (1, Card::from_str(line))).collect();
-    let mut sum: usize = 0;
-    while let Some((copies, card)) = cards.pop_front() {
-        cards.iter_mut()
-            .take(card.matching_numbers_count())
-            .for_each(|entry| entry.0 += copies);
-        sum += copies;
-    }
-    return sum;
-}
-
-#[derive(Debug)]
-struct Card {
-    id: usize,
-    winning_numbers: Vec<usize>,
-    actual_numbers: Vec<usize>,
-}
-
-impl Card {
-    fn from_str(input: &str) -> Card {
-        let card_regex = Regex::new(r"^Card\W+(?<card_id>\d+): (?<winning_numbers>.*) \| (?<actual_numbers>.*)").expect("Invalid game match regex");
-        let captures = card_regex.captures(input).expect("Invalid input");
-
-        let card_id = captures["card_id"].parse::<usize>().unwrap();
-
-        let winning_numbers = captures["winning_numbers"].split(' ').filter_map(|winning_number| winning_number.parse::<usize>().ok()).collect();
-        let actual_numbers = captures["actual_numbers"].split(' ').filter_map(|number| number.parse::<usize>().ok()).collect();
-        return Card { id: card_id, winning_numbers, actual_numbers };
-    }
-    fn matching_numbers_count(self: &Card) -> usize {
-        self.actual_numbers.iter()
-            .filter(|number| self.winning_numbers.contains(number))
-            .count()
-    }
-
-    fn score(self: &Card) -> usize {
-        let matching_count = self.matching_numbers_count();
-        if matching_count == 0 { return 0; }
-        let base: usize = 2;
-        return base.pow((matching_count - 1) as u32);
-    }
-}
-
-#[cfg(test)]
-mod test_day04 {
-    use super::*;
-
-    #[test]
-    fn test_part1() {
-        assert_eq!(part1(include_str!("../resources/example1.txt")), 13);
-    }
-
-    #[test]
-    fn test_part2() {
-        assert_eq!(part2(include_str!("../resources/example1.txt")), 30);
-    }
-
-    #[test]
-    fn test_card_parser() {
-        let card = Card::from_str("Card  3: 41 48 83 86 17 | 83 86  6 31 17  9 48 53");
-        assert_eq!(card.id, 3);
-        assert_eq!(card.winning_numbers, [41, 48, 83, 86, 17]);
-        assert_eq!(card.actual_numbers, [83, 86, 6, 31, 17, 9, 48, 53]);
-        assert_eq!(card.matching_numbers_count(), 4);
-        assert_eq!(card.score(), 8);
-    }
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+use regex::Regex;
+
+use aoc_common::parse::{parse_lines, parse_number};
+use aoc_common::{checked, parallel, ParseError, Solution, SolveError};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    type Params = ();
+    type Input<'a> = Vec<Card>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
+        parse_lines(input, Card::from_str)
+    }
+
+    fn part1(cards: &Vec<Card>, _: &()) -> Result<usize, SolveError> {
+        return parallel::try_sum(cards, Card::score);
+    }
+
+    fn part2(cards: &Vec<Card>, _: &()) -> Result<usize, SolveError> {
+        let mut cards: VecDeque<(usize, &Card)> = cards.iter().map(|card| (1, card)).collect();
+        let mut sum: usize = 0;
+        while let Some((copies, card)) = cards.pop_front() {
+            for entry in cards.iter_mut().take(card.matching_numbers_count()) {
+                entry.0 = checked::add(entry.0, copies)?;
+            }
+            sum = checked::add(sum, copies)?;
+        }
+        return Ok(sum);
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day04::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day04::solve_part2(input)
+}
+
+#[derive(Debug)]
+pub struct Card {
+    #[allow(dead_code)] // Only read by the tests.
+    id: usize,
+    winning_numbers: Vec<usize>,
+    actual_numbers: Vec<usize>,
+}
+
+impl Card {
+    fn from_str(input: &str) -> Result<Card, ParseError> {
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        let card_regex = REGEX.get_or_init(|| Regex::new(r"^Card\W+(?<card_id>\d+): (?<winning_numbers>.*) \| (?<actual_numbers>.*)").expect("Invalid game match regex"));
+        let captures = card_regex.captures(input)
+            .ok_or_else(|| ParseError::locate(input, input, "expected `Card <id>: <winning numbers> | <numbers>`"))?;
+
+        let card_id = parse_number(input, captures.name("card_id").unwrap().as_str())?;
+
+        let winning_numbers = Card::parse_numbers(input, captures.name("winning_numbers").unwrap().as_str())?;
+        let actual_numbers = Card::parse_numbers(input, captures.name("actual_numbers").unwrap().as_str())?;
+        return Ok(Card { id: card_id, winning_numbers, actual_numbers });
+    }
+
+    fn parse_numbers(input: &str, numbers: &str) -> Result<Vec<usize>, ParseError> {
+        return numbers.split(' ')
+            .filter(|number| !number.is_empty())
+            .map(|number| parse_number(input, number))
+            .collect();
+    }
+
+    fn matching_numbers_count(self: &Card) -> usize {
+        self.actual_numbers.iter()
+            .filter(|number| self.winning_numbers.contains(number))
+            .count()
+    }
+
+    fn score(self: &Card) -> Result<usize, SolveError> {
+        let matching_count = self.matching_numbers_count();
+        if matching_count == 0 { return Ok(0); }
+        let base: usize = 2;
+        return u32::try_from(matching_count - 1).ok()
+            .and_then(|exponent| base.checked_pow(exponent))
+            .ok_or_else(checked::overflow::<usize>);
+    }
+}
+
+#[cfg(test)]
+mod test_day04 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
+    use super::*;
+
+    /// Cards as their winning numbers and the numbers you have, both without duplicates.
+    fn cards() -> impl Strategy<Value = Vec<(Vec<usize>, Vec<usize>)>> {
+        let numbers = |size| prop::collection::btree_set(1..40usize, size).prop_map(Vec::from_iter).prop_shuffle();
+        return prop::collection::vec((numbers(1..6), numbers(1..10)), 1..10);
+    }
+
+    /// Skips parsing, which is covered by the examples, to keep the many random cases fast.
+    fn to_cards(cards: &[(Vec<usize>, Vec<usize>)]) -> Vec<Card> {
+        return cards.iter().enumerate()
+            .map(|(index, (winning, actual))| Card { id: index + 1, winning_numbers: winning.clone(), actual_numbers: actual.clone() })
+            .collect();
+    }
+
+    fn matches(card: &(Vec<usize>, Vec<usize>)) -> usize {
+        return card.1.iter().filter(|number| card.0.contains(number)).count();
+    }
+
+    /// The number of cards you end up with by scratching card `index`: itself, and everything its copies win.
+    fn cards_won_oracle(cards: &[(Vec<usize>, Vec<usize>)], index: usize) -> usize {
+        let last = (index + matches(&cards[index])).min(cards.len() - 1);
+        return 1 + (index + 1..=last).map(|won| cards_won_oracle(cards, won)).sum::<usize>();
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle(cards in cards()) {
+            let expected: usize = cards.iter()
+                .map(|card| match matches(card) {
+                    0 => 0,
+                    count => (1..count).fold(1, |score, _| score * 2),
+                })
+                .sum();
+            prop_assert_eq!(Day04::part1(&to_cards(&cards), &()), Ok(expected));
+        }
+
+        #[test]
+        fn test_part2_matches_oracle(cards in cards()) {
+            let expected: usize = (0..cards.len()).map(|index| cards_won_oracle(&cards, index)).sum();
+            prop_assert_eq!(Day04::part2(&to_cards(&cards), &()), Ok(expected));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs resources/example1.txt, run with --include-ignored"]
+    fn test_part1() {
+        let input = example!("example1.txt");
+        assert_eq!(part1(&input), Ok(13));
+    }
+
+    #[test]
+    #[ignore = "needs resources/example1.txt, run with --include-ignored"]
+    fn test_part2() {
+        let input = example!("example1.txt");
+        assert_eq!(part2(&input), Ok(30));
+    }
+
+    #[test]
+    fn test_card_parser() {
+        let card = Card::from_str("Card  3: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
+        assert_eq!(card.id, 3);
+        assert_eq!(card.winning_numbers, [41, 48, 83, 86, 17]);
+        assert_eq!(card.actual_numbers, [83, 86, 6, 31, 17, 9, 48, 53]);
+        assert_eq!(card.matching_numbers_count(), 4);
+        assert_eq!(card.score(), Ok(8));
+    }
+
+    #[test]
+    fn test_card_parser_error() {
+        let err = Card::from_str("Card  3: 41 48 8x 86 17 | 83 86").unwrap_err();
+        assert_eq!(err, ParseError::new(1, 16, "8x", "expected a number"));
+        assert!(Card::from_str("Card 3: 41 48").is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let numbers = (1..=65).map(|number| number.to_string()).collect::<Vec<_>>().join(" ");
+        assert_eq!(part1(&format!("Card 1: {numbers} | {numbers}")), Err(checked::overflow::<usize>()));
+        // Every card scores 2^63, which fits, but two of them do not.
+        let numbers = (1..=64).map(|number| number.to_string()).collect::<Vec<_>>().join(" ");
+        assert_eq!(part1(&format!("Card 1: {numbers} | {numbers}")), Ok(1 << 63));
+        assert_eq!(part1(&format!("Card 1: {numbers} | {numbers}\nCard 2: {numbers} | {numbers}")), Err(checked::overflow::<usize>()));
+        let cards = (1..=70).map(|id| format!("Card {id}: {numbers} | {numbers}")).collect::<Vec<_>>().join("\n");
+        assert_eq!(part2(&cards), Err(checked::overflow::<usize>()));
+    }
 }
\ No newline at end of file
@@ -1,161 +1,272 @@
-use std::ops::Range;
-use nom::sequence::{pair, preceded, separated_pair, tuple};
-use nom::bytes::complete::{tag, take_until};
-use nom::{IResult};
-use nom::character::complete::{self, line_ending, newline, space1};
+use nom::sequence::{pair, preceded, tuple};
+use nom::IResult;
+use nom::character::complete::{self, line_ending, multispace0, newline, space1};
+use nom::combinator::{consumed, eof};
 use nom::multi::{many1, separated_list1};
+use nom::Parser;
+use nom_supreme::error::ErrorTree;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
+use tracing::debug;
 
-fn parse_seeds_p1(input: &str) -> IResult<&str, Vec<u64>> {
+use aoc_common::parse::from_nom;
+use aoc_common::{checked, ParseError, Solution, SolveError};
+use aoc_interval::{RangeMap, RangeSet};
+
+type ParseResult<'a, T> = IResult<&'a str, T, ErrorTree<&'a str>>;
+
+/// The maps of the almanac, in the order they have to be applied.
+const SECTIONS: [&str; 7] = [
+    "seed-to-soil map",
+    "soil-to-fertilizer map",
+    "fertilizer-to-water map",
+    "water-to-light map",
+    "light-to-temperature map",
+    "temperature-to-humidity map",
+    "humidity-to-location map",
+];
+
+fn parse_seeds_p1(input: &str) -> ParseResult<'_, Vec<u64>> {
     return preceded(
         tag("seeds: "),
         separated_list1(space1, complete::u64)
-    )(input);
+    ).context("seeds").parse(input);
 }
 
-fn parse_seeds_p2(input: &str) -> IResult<&str, Vec<(u64, u64)>> {
-    return preceded(
-        tag("seeds: "),
-        separated_list1(space1, separated_pair(complete::u64, space1, complete::u64))
-    )(input);
+fn parse_mapping(input: &str) -> ParseResult<'_, (u64, u64, u64)> {
+    // Once a line starts with a number, the rest of it has to be a mapping as well.
+    return tuple((
+        complete::u64,
+        preceded(space1, complete::u64).cut(),
+        preceded(space1, complete::u64).cut(),
+    ))(input);
 }
 
-fn parse_map(input: &str) -> IResult<&str, Vec<(u64, u64, u64)>> {
+/// A mapping as `(destination, source, length)`, with the line it was read from.
+type Mapping<'a> = (&'a str, (u64, u64, u64));
+
+fn parse_map<'a>(section: &'static str) -> impl FnMut(&'a str) -> ParseResult<'a, Vec<Mapping<'a>>> {
     return preceded(
-        take_until("map:"),
-        preceded(
-            pair(tag("map:"), newline),
-            separated_list1(
-                newline,
-                tuple((complete::u64, preceded(space1, complete::u64), preceded(space1, complete::u64)))
-            )
-        )
-    )(input);
+        tuple((tag(section), tag(":"), newline)),
+        separated_list1(newline, consumed(parse_mapping)).context(section)
+    );
+}
+
+fn parse_maps(input: &str) -> ParseResult<'_, Vec<Vec<Mapping<'_>>>> {
+    let mut seed_maps = vec![];
+    let mut input = input;
+    for section in SECTIONS {
+        let (rest, seed_map) = preceded(many1(line_ending), parse_map(section))(input)?;
+        seed_maps.push(seed_map);
+        input = rest;
+    }
+    let (input, _) = pair(multispace0, eof)(input)?;
+    return Ok((input, seed_maps));
 }
 
-fn parse_and_process_map(input: &str) -> IResult<&str, SeedMap> {
-    let (input, seed_map) = parse_map(input).expect("Valid seed map");
-    let seed_map = SeedMap {
-        mappings: seed_map.iter().map(|mapping| {
-            SeedMapping {
-                source: mapping.1..(mapping.1 + mapping.2),
-                destination: mapping.0..(mapping.0 + mapping.2),
+pub struct Day05;
+
+#[derive(Debug)]
+pub struct Almanac {
+    seeds: Vec<u64>,
+    seed_maps: Vec<RangeMap<u64>>,
+}
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    type Params = ();
+    type Input<'a> = Almanac;
+    type Answer = u64;
+
+    fn parse(input: &str) -> Result<Almanac, ParseError> {
+        let (_, (seeds, maps)) = pair(parse_seeds_p1, parse_maps)(input)
+            .map_err(|err| from_nom(input, err))?;
+        let mut seed_maps = vec![];
+        for mappings in maps {
+            let mut seed_map = RangeMap::new();
+            for (line, (destination, source, length)) in mappings {
+                let (Some(end), Some(_)) = (source.checked_add(length), destination.checked_add(length)) else {
+                    return Err(ParseError::locate(input, line, "maps values beyond the largest number"));
+                };
+                if !seed_map.insert(source..end, destination) {
+                    return Err(ParseError::locate(input, line, "overlaps an earlier mapping"));
+                }
             }
-        }).collect(),
-    };
+            seed_maps.push(seed_map);
+        }
+        return Ok(Almanac { seeds, seed_maps });
+    }
+
+    fn part1(almanac: &Almanac, _: &()) -> Result<u64, SolveError> {
+        let seed_to_location = almanac.seed_to_location();
+        return almanac.seeds.iter().map(|seed| seed_to_location.get(*seed)).min()
+            .ok_or_else(|| SolveError::no_answer("there are no seeds"));
+    }
 
-    return Ok((input, seed_map));
+    fn part2(almanac: &Almanac, _: &()) -> Result<u64, SolveError> {
+        // For part 2 the seeds line lists pairs of range start and range length.
+        let seeds: RangeSet<u64> = almanac.seeds.chunks(2)
+            .map(|pair| match pair {
+                [start, length] => Ok(*start..checked::add(*start, *length)?),
+                _ => Err(SolveError::no_answer("the seeds do not come in pairs of a start and a length")),
+            })
+            .collect::<Result<_, SolveError>>()?;
+        let locations = almanac.seed_to_location().map_set(&seeds);
+        debug!(seeds = seeds.ranges().len(), locations = locations.ranges().len(), "split the seed ranges");
+        return locations.min().ok_or_else(|| SolveError::no_answer("there are no seeds"));
+    }
 }
 
-fn parse_maps(input: &str) -> IResult<&str, Vec<SeedMap>> {
-    return preceded(
-        many1(line_ending),
-        separated_list1(line_ending, parse_and_process_map)
-    )(input);
+impl Almanac {
+    /// All maps of the almanac in one, taking a seed straight to its location.
+    fn seed_to_location(self: &Almanac) -> RangeMap<u64> {
+        return self.seed_maps.iter().enumerate().fold(RangeMap::new(), |composed, (index, seed_map)| {
+            let composed = composed.compose(seed_map);
+            debug!(map = index, pieces = seed_map.pieces().len(), composed = composed.pieces().len(), "composed a map");
+            return composed;
+        });
+    }
 }
 
-pub fn part1(input: &str) -> u64 {
-    let (input, seeds) = parse_seeds_p1(input).expect("Valid seeds");
-    let (_, seed_maps) = parse_maps(input).expect("Valid map");
-    let mapped = seed_maps.iter().fold(seeds, |cur, seed_map| {
-        cur.iter().map(|seed| seed_map.map_seed(seed)).collect()
-    });
-    return *mapped.iter().min().unwrap();
+pub fn part1(input: &str) -> Result<u64, SolveError> {
+    Day05::solve_part1(input)
 }
 
-pub fn part2(input: &str) -> u64 {
-    let (input, seeds) = parse_seeds_p2(input).expect("Valid seeds");
-    let seeds: Vec<Range<u64>> = seeds.iter()
-        .map(|(start, length)| { *start..(start + length) })
-        .collect();
-    let (_, seed_maps) = parse_maps(input).expect("Valid map");
-
-    let mapped_ranges = seed_maps.iter().fold(seeds, |cur, seed_map| {
-        cur.iter().flat_map(|seed| seed_map.map_seed_range(seed)).collect()
-    });
-    return mapped_ranges.iter().map(|range| range.start).min().unwrap();
+pub fn part2(input: &str) -> Result<u64, SolveError> {
+    Day05::solve_part2(input)
 }
 
-#[derive(Debug)]
+#[cfg(test)]
+mod test_day05 {
+    use proptest::prelude::*;
 
-struct SeedMapping {
-    source: Range<u64>,
-    destination: Range<u64>
-}
+    use aoc_common::example;
 
-impl SeedMapping {
-    fn identity(start: u64, end: u64) -> SeedMapping {
-        SeedMapping {
-            source: start..end,
-            destination: start..end,
-        }
-    }
+    use super::*;
 
-    pub fn translate(self: &SeedMapping, seed: u64) -> u64 {
-        seed - self.source.start + self.destination.start
+    /// A map as `(destination, source, length)` triples, with sources that don't overlap.
+    fn mappings() -> impl Strategy<Value = Vec<(u64, u64, u64)>> {
+        let bounds = prop::collection::btree_set(0..100u64, 2..9);
+        let destinations = prop::collection::vec(0..100u64, 4);
+        return (bounds, destinations).prop_map(|(bounds, destinations)| {
+            let bounds: Vec<u64> = bounds.into_iter().collect();
+            return bounds.chunks_exact(2).zip(destinations)
+                .map(|(source, destination)| (destination, source[0], source[1] - source[0]))
+                .collect();
+        });
     }
 
-    pub fn clone(self: &SeedMapping) -> SeedMapping {
-        SeedMapping {
-            source: self.source.start..self.source.end,
-            destination: self.destination.start..self.destination.end,
+    fn seed_map(mappings: &[(u64, u64, u64)]) -> RangeMap<u64> {
+        let mut seed_map = RangeMap::new();
+        for (destination, source, length) in mappings {
+            assert!(seed_map.insert(*source..source + length, *destination));
         }
+        return seed_map;
     }
-}
 
-#[derive(Debug)]
-struct SeedMap {
-    mappings: Vec<SeedMapping>
-}
+    /// Looks the seed up in every mapping, keeping it as is when none of them contains it.
+    fn map_seed_oracle(mappings: &[(u64, u64, u64)], seed: u64) -> u64 {
+        return mappings.iter()
+            .find(|(_, source, length)| (*source..source + length).contains(&seed))
+            .map_or(seed, |(destination, source, _)| seed - source + destination);
+    }
 
-impl SeedMap {
-    fn lowest_after(self: &SeedMap, seed: &u64) -> u64 {
-        self.mappings.iter()
-            .map(|mapping| mapping.source.start)
-            .filter(|start| start > seed)
-            .min().unwrap_or(u64::MAX)
-    }
-    fn find_map(self: &SeedMap, seed: &u64) -> SeedMapping {
-        return match self.mappings.iter().find(|map| map.source.contains(seed)) {
-            Some(seed_map) => seed_map.clone(),
-            None =>  SeedMapping::identity(*seed, self.lowest_after(seed)),
-        }
+    fn render(seeds: &[u64], maps: &[Vec<(u64, u64, u64)>]) -> String {
+        let seeds = seeds.iter().map(u64::to_string).collect::<Vec<_>>().join(" ");
+        let maps = SECTIONS.iter().zip(maps)
+            .map(|(section, mappings)| {
+                let mappings = mappings.iter().map(|(destination, source, length)| format!("{destination} {source} {length}"));
+                return format!("{section}:\n{}", mappings.collect::<Vec<_>>().join("\n"));
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        return format!("seeds: {seeds}\n\n{maps}\n");
     }
-    pub fn map_seed(self: &SeedMap, seed: &u64) -> u64 {
-        self.find_map(seed).translate(*seed)
+
+    fn location_oracle(maps: &[Vec<(u64, u64, u64)>], seed: u64) -> u64 {
+        return maps.iter().fold(seed, |seed, mappings| map_seed_oracle(mappings, seed));
     }
 
-    pub fn map_seed_range(self: &SeedMap, seed_range: &Range<u64>) -> Vec<Range<u64>> {
-        let mut result: Vec<Range<u64>> = vec![];
-        let mut seed_range_left = Some(seed_range.clone());
-        while let Some(seed_range) = seed_range_left {
-            let mapping = self.find_map(&seed_range.start);
-            let mapped_end = mapping.source.end;
-            if mapped_end < seed_range.end {
-                result.push(mapping.translate(seed_range.start)..mapping.translate(mapped_end));
-                seed_range_left = Some(mapped_end..seed_range.end);
+    proptest! {
+        #[test]
+        fn test_map_range_matches_oracle(mappings in mappings(), start in 0..120u64, length in 0..40u64) {
+            let mut expected: Vec<u64> = (start..start + length).map(|seed| map_seed_oracle(&mappings, seed)).collect();
+            expected.sort();
+            let mut actual: Vec<u64> = seed_map(&mappings).map_range(start..start + length).into_iter().flatten().collect();
+            actual.sort();
+            prop_assert_eq!(actual, expected);
+        }
 
-            } else {
-                result.push(mapping.translate(seed_range.start)..mapping.translate(seed_range.end));
-                seed_range_left = None;
-            }
+        #[test]
+        fn test_parts_match_oracle(
+            seeds in prop::collection::vec((0..120u64, 1..30u64), 1..4),
+            maps in prop::collection::vec(mappings(), SECTIONS.len()),
+        ) {
+            let seeds: Vec<u64> = seeds.into_iter().flat_map(|(start, length)| [start, length]).collect();
+            let input = render(&seeds, &maps);
+            let expected_part1 = seeds.iter().map(|seed| location_oracle(&maps, *seed)).min();
+            let expected_part2 = seeds.chunks(2)
+                .flat_map(|pair| pair[0]..pair[0] + pair[1])
+                .map(|seed| location_oracle(&maps, seed))
+                .min();
+            prop_assert_eq!(part1(&input).ok(), expected_part1);
+            prop_assert_eq!(part2(&input).ok(), expected_part2);
         }
-        return result;
     }
-}
-
-#[cfg(test)]
-mod test_day05 {
-    use super::*;
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part1() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part1(input), 35);
+        let input = &example!("example.txt");
+        assert_eq!(part1(input), Ok(35));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part2() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part2(input), 46);
+        let input = &example!("example.txt");
+        assert_eq!(part2(input), Ok(46));
     }
-}
\ No newline at end of file
+
+    /// An almanac of `seeds` whose seed-to-soil map is `seed_to_soil`, the other maps only moving 1000.
+    fn almanac(seeds: &[u64], seed_to_soil: Vec<(u64, u64, u64)>) -> String {
+        let maps: Vec<Vec<(u64, u64, u64)>> = std::iter::once(seed_to_soil)
+            .chain(std::iter::repeat_n(vec![(0, 1000, 1)], SECTIONS.len() - 1))
+            .collect();
+        return render(seeds, &maps);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 x 48";
+        let err = Day05::parse(input).unwrap_err();
+        assert_eq!(err.to_string(), "line 5, column 4: expected an ascii digit in seed-to-soil map (found `x 48`)");
+
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n\nsoil-to-fertiliser map:\n0 15 37";
+        let err = Day05::parse(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.message.as_str()), (6, 1, "expected \"soil-to-fertilizer map\""));
+
+        let err = Day05::parse("seeds: 79 x4").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 10));
+
+        let input = almanac(&[79, 14], vec![(50, 98, 2), (52, 50, 49)]);
+        let err = Day05::parse(&input).unwrap_err();
+        assert_eq!(err, ParseError::new(5, 1, "52 50 49", "overlaps an earlier mapping"));
+
+        let input = almanac(&[79, 14], vec![(50, 98, 2), (18446744073709551610, 50, 48)]);
+        let err = Day05::parse(&input).unwrap_err();
+        assert_eq!((err.line, err.column, err.message.as_str()), (5, 1, "maps values beyond the largest number"));
+    }
+
+    #[test]
+    fn test_no_answer() {
+        let seed_to_soil = vec![(50, 98, 2), (52, 50, 48)];
+        let input = almanac(&[79, 14, 55], seed_to_soil.clone());
+        assert_eq!(part1(&input), Ok(14));
+        assert_eq!(part2(&input), Err(SolveError::no_answer("the seeds do not come in pairs of a start and a length")));
+        let input = almanac(&[79, 0], seed_to_soil.clone());
+        assert_eq!(part2(&input), Err(SolveError::no_answer("there are no seeds")));
+        let input = almanac(&[18446744073709551615, 1], seed_to_soil);
+        assert_eq!(part2(&input), Err(checked::overflow::<u64>()));
+    }
+}
@@ -1,28 +1,72 @@
-pub fn part1(input: &str) -> usize {
-    let raw_input: Vec<usize> = input.split_whitespace().into_iter()
-        .filter_map(|s| s.parse::<usize>().ok())
-        .collect();
-    let (times, distances) = raw_input.split_at(raw_input.len() / 2);
-    return times.iter()
-        .zip(distances)
-        .map(|(time, distance)| Race { time: *time, distance: *distance })
-        .map(|race| race.n_ways_to_win())
-        .product();
+use aoc_common::parse::parse_number;
+use aoc_common::{checked, ParseError, Solution, SolveError};
+
+pub struct Day06;
+
+/// The numbers of the time and distance rows, kept as text since part 2 reads
+/// them as one big number with the spaces removed.
+#[derive(Debug)]
+pub struct RaceSheet<'a> {
+    times: Vec<&'a str>,
+    distances: Vec<&'a str>,
 }
 
-fn numeric(input: &&str) -> bool {
-    match input.parse::<usize>() {
-        Ok(_) => true,
-        Err(_) => false,
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    type Params = ();
+    type Input<'a> = RaceSheet<'a>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<RaceSheet<'_>, ParseError> {
+        let mut lines = input.lines();
+        let times = parse_row(input, lines.next(), "Time:")?;
+        let distances_line = lines.next();
+        let distances = parse_row(input, distances_line, "Distance:")?;
+        if times.len() != distances.len() {
+            let message = format!("expected {} distances, one for every time", times.len());
+            return Err(ParseError::locate(input, distances_line.unwrap_or_default(), message));
+        }
+        return Ok(RaceSheet { times, distances });
+    }
+
+    fn part1(sheet: &RaceSheet, _: &()) -> Result<usize, SolveError> {
+        let races = sheet.times.iter()
+            .zip(&sheet.distances)
+            .map(|(time, distance)| Ok(Race { time: join_number(&[time])?, distance: join_number(&[distance])? }))
+            .collect::<Result<Vec<Race>, SolveError>>()?;
+        return checked::product(races.iter().map(|race| race.n_ways_to_win()));
+    }
+
+    fn part2(sheet: &RaceSheet, _: &()) -> Result<usize, SolveError> {
+        let time = join_number(&sheet.times)?;
+        let distance = join_number(&sheet.distances)?;
+        return Ok((Race { time, distance, }).n_ways_to_win());
     }
 }
 
-pub fn part2(input: &str) -> usize {
-    let raw_input: Vec<&str> = input.split_whitespace().into_iter().filter(numeric).collect();
-    let (times, distances) = raw_input.split_at(raw_input.len() / 2);
-    let time = times.iter().fold("".to_owned(), |res, cur| res + cur).parse::<usize>().unwrap();
-    let distance = distances.iter().fold("".to_owned(), |res, cur| res + cur).parse::<usize>().unwrap();
-    return (Race { time, distance, }).n_ways_to_win();
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day06::solve_part1(input)
+}
+
+/// The number written by the digits of all `numbers` one after the other.
+fn join_number(numbers: &[&str]) -> Result<usize, SolveError> {
+    if numbers.is_empty() {
+        return Err(SolveError::no_answer("there are no races"));
+    }
+    return numbers.concat().parse().map_err(|_| checked::overflow::<usize>());
+}
+
+fn parse_row<'a>(input: &'a str, line: Option<&'a str>, label: &str) -> Result<Vec<&'a str>, ParseError> {
+    let line = line.ok_or_else(|| ParseError::locate(input, &input[input.len()..], format!("expected a `{label}` line")))?;
+    let numbers = line.strip_prefix(label)
+        .ok_or_else(|| ParseError::locate(input, line, format!("expected a line starting with `{label}`")))?;
+    return numbers.split_whitespace()
+        .map(|number| parse_number::<usize>(input, number).map(|_| number))
+        .collect();
+}
+
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day06::solve_part2(input)
 }
 
 #[derive(Debug)]
@@ -33,27 +77,88 @@ struct Race {
 
 impl Race {
     pub fn n_ways_to_win(self: &Race) -> usize {
-        let first_win = (1..self.time).into_iter().find(|n| (self.time - n) * n > self.distance).unwrap();
-        return if self.time % 2 == 0 {
-            self.time - (2 * (first_win - 1)) - 1
-        } else {
-            self.time - (2 * first_win) + 1
-        };
+        let wins = |hold: usize| (self.time - hold) as u128 * hold as u128 > self.distance as u128;
+        // Holding longer goes further up to half the time, and the wins are symmetric around it.
+        let half = self.time / 2;
+        if !wins(half) {
+            return 0;
+        }
+        let (mut lose, mut win) = (0, half);
+        while win - lose > 1 {
+            let middle = lose + (win - lose) / 2;
+            if wins(middle) { win = middle } else { lose = middle }
+        }
+        return self.time - 2 * win + 1;
     }
 }
 
 
+#[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    /// Races that can be won, so the record distance is below the best distance possible.
+    fn race() -> impl Strategy<Value = (usize, usize)> {
+        return (2..1000usize).prop_flat_map(|time| (Just(time), 0..(time / 2) * (time - time / 2)));
+    }
+
+    fn n_ways_to_win_oracle(time: usize, distance: usize) -> usize {
+        return (0..=time).filter(|hold| hold * (time - hold) > distance).count();
+    }
+
+    proptest! {
+        #[test]
+        fn test_n_ways_to_win_matches_oracle((time, distance) in race()) {
+            prop_assert_eq!(Race { time, distance }.n_ways_to_win(), n_ways_to_win_oracle(time, distance));
+        }
+
+        #[test]
+        fn test_n_ways_to_win_any_record(time in 0..200usize, distance in 0..12000usize) {
+            prop_assert_eq!(Race { time, distance }.n_ways_to_win(), n_ways_to_win_oracle(time, distance));
+        }
+
+        #[test]
+        fn test_part1_matches_oracle(races in prop::collection::vec(race(), 1..5)) {
+            let times = races.iter().map(|(time, _)| format!(" {time:>6}")).collect::<String>();
+            let distances = races.iter().map(|(_, distance)| format!(" {distance:>6}")).collect::<String>();
+            let expected: usize = races.iter().map(|(time, distance)| n_ways_to_win_oracle(*time, *distance)).product();
+            prop_assert_eq!(part1(&format!("Time:{times}\nDistance:{distances}")), Ok(expected));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     pub fn test_part1() {
-        assert_eq!(part1(include_str!("../resources/example.txt")), 288);
+        let input = example!("example.txt");
+        assert_eq!(part1(&input), Ok(288));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     pub fn test_part2() {
-        assert_eq!(part2(include_str!("../resources/example.txt")), 71503);
+        let input = example!("example.txt");
+        assert_eq!(part2(&input), Ok(71503));
+
+    }
 
+    #[test]
+    pub fn test_parse_error() {
+        assert_eq!(Day06::parse("Time: 7 15\nDistance: 9 4O").unwrap_err(), ParseError::new(2, 13, "4O", "expected a number"));
+        assert_eq!(Day06::parse("Time: 7 15\nDistance: 9").unwrap_err().line, 2);
+        assert_eq!(Day06::parse("Time: 7 15").unwrap_err().message, "expected a `Distance:` line");
+    }
+
+    #[test]
+    pub fn test_huge_races() {
+        assert_eq!(Race { time: usize::MAX, distance: 0 }.n_ways_to_win(), usize::MAX - 1);
+        assert_eq!(Race { time: 10, distance: usize::MAX }.n_ways_to_win(), 0);
+        let input = format!("Time: {} 1\nDistance: 0 0", usize::MAX);
+        assert_eq!(part1(&input), Ok(0));
+        assert_eq!(part2(&input), Err(checked::overflow::<usize>()));
+        assert_eq!(part2("Time:\nDistance:"), Err(SolveError::no_answer("there are no races")));
     }
 }
\ No newline at end of file
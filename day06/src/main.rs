@@ -1,7 +1,8 @@
-use day06::{part1, part2};
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../resources/input.txt");
-    println!("part1: {}", part1(input));
-    println!("part2: {}", part2(input));
+use aoc_common::inputs;
+use day06::Day06;
+
+fn main() -> ExitCode {
+    aoc_common::run::<Day06>(inputs!())
 }
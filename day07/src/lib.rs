@@ -1,33 +1,71 @@
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> usize {
-    let hands: Vec<HandPart1> = input.lines()
-        .map(|line| line.split_once(" ").unwrap())
-        .map(HandPart1::from)
-        .collect();
-    return hands.iter()
-        .sorted_by_key(|h| h.score)
-        .enumerate()
-        .map(|(rank, hand)| hand.bid * (rank + 1))
-        .sum();
+use aoc_common::parse::{parse_lines, parse_number};
+use aoc_common::{checked, ParseError, Solution, SolveError};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    type Params = ();
+    /// Every hand as its five cards and the bid placed on it.
+    type Input<'a> = Vec<(&'a str, usize)>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Vec<(&str, usize)>, ParseError> {
+        parse_lines(input, parse_hand)
+    }
+
+    fn part1(hands: &Vec<(&str, usize)>, _: &()) -> Result<usize, SolveError> {
+        let hands: Vec<HandPart1> = hands.iter()
+            .map(|hand| HandPart1::from(*hand))
+            .collect();
+        let winnings = hands.iter()
+            .sorted_by(|a, b| a.score.cmp(&b.score))
+            .enumerate()
+            .map(|(rank, hand)| checked::mul(hand.bid, rank + 1))
+            .collect::<Result<Vec<usize>, SolveError>>()?;
+        return checked::sum(winnings);
+    }
+
+    fn part2(hands: &Vec<(&str, usize)>, _: &()) -> Result<usize, SolveError> {
+        let hands: Vec<HandPart2> = hands.iter()
+            .map(|hand| HandPart2::from(*hand))
+            .collect();
+        let winnings = hands.iter()
+            .sorted_by(|a, b| a.score.cmp(&b.score))
+            .enumerate()
+            .map(|(rank, hand)| checked::mul(hand.bid, rank + 1))
+            .collect::<Result<Vec<usize>, SolveError>>()?;
+        return checked::sum(winnings);
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day07::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day07::solve_part2(input)
 }
 
-pub fn part2(input: &str) -> usize {
-    let hands: Vec<HandPart2> = input.lines()
-        .map(|line| line.split_once(" ").unwrap())
-        .map(HandPart2::from)
-        .collect();
-    return hands.iter()
-        .sorted_by_key(|h| h.score)
-        .enumerate()
-        .map(|(rank, hand)| hand.bid * (rank + 1))
-        .sum();
+fn parse_hand(line: &str) -> Result<(&str, usize), ParseError> {
+    let (hand, bid) = line.split_once(' ')
+        .ok_or_else(|| ParseError::locate(line, line, "expected `<hand> <bid>`"))?;
+    if let Some((index, _)) = hand.char_indices().find(|(_, card)| !"23456789TJQKA".contains(*card)) {
+        return Err(ParseError::locate(line, &hand[index..], "invalid card"));
+    }
+    if hand.len() != 5 {
+        return Err(ParseError::locate(line, hand, "expected a hand of five cards"));
+    }
+    return Ok((hand, parse_number(line, bid)?));
 }
 
 #[derive(Debug)]
 struct HandPart1 {
     bid: usize,
-    score: (HandType, u8, u8, u8, u8, u8)
+    /// The type of the hand, then its cards in order to break ties.
+    score: (HandType, Vec<u8>),
 }
 impl HandPart1 {
     fn cards_to_type(cards: &str) -> HandType {
@@ -57,36 +95,36 @@ impl HandPart1 {
         }
     }
 
-    pub fn from((hand, bid): (&str, &str)) -> HandPart1 {
+    pub fn from((hand, bid): (&str, usize)) -> HandPart1 {
         let hand_type = HandPart1::cards_to_type(hand);
         return HandPart1 {
-            bid: bid.parse::<usize>().unwrap(),
+            bid,
             score: HandPart1::score(hand, hand_type),
         };
     }
 
-    fn score(cards: &str, hand_type: HandType) -> (HandType, u8, u8, u8, u8, u8) {
-        let cards: Vec<u8> = cards.chars().into_iter().map(HandPart1::card_to_int).collect();
-        return (hand_type, cards[0], cards[1], cards[2], cards[3], cards[4]);
+    fn score(cards: &str, hand_type: HandType) -> (HandType, Vec<u8>) {
+        return (hand_type, cards.chars().map(HandPart1::card_to_int).collect());
     }
 }
 
 #[derive(Debug)]
 struct HandPart2 {
     bid: usize,
-    score: (HandType, u8, u8, u8, u8, u8)
+    /// The type of the hand, then its cards in order to break ties.
+    score: (HandType, Vec<u8>),
 }
 impl HandPart2 {
     fn cards_to_type(cards: &str) -> HandType {
         let card_counts = cards.chars().counts();
-        let jokers = card_counts.get(&'J').unwrap_or(&0).clone();
+        let jokers = *card_counts.get(&'J').unwrap_or(&0);
         let card_counts = card_counts.into_iter()
             .filter(|(c, _)| *c != 'J')
             .map(|(_, v)| v)
             .sorted()
             .rev()
             .collect::<Vec<usize>>();
-        let (biggest, second_biggest) = (*card_counts.get(0).unwrap_or(&0) + jokers, *card_counts.get(1).unwrap_or(&0));
+        let (biggest, second_biggest) = (*card_counts.first().unwrap_or(&0) + jokers, *card_counts.get(1).unwrap_or(&0));
         if biggest == 5 { return HandType::FiveOfAKind; }
         if biggest == 4{ return HandType::FourOfAKind; }
         if biggest == 3 && second_biggest == 2 { return HandType::FullHouse; }
@@ -107,17 +145,16 @@ impl HandPart2 {
         }
     }
 
-    pub fn from((hand, bid): (&str, &str)) -> HandPart2 {
+    pub fn from((hand, bid): (&str, usize)) -> HandPart2 {
         let hand_type = HandPart2::cards_to_type(hand);
         return HandPart2 {
-            bid: bid.parse::<usize>().unwrap(),
+            bid,
             score: HandPart2::score(hand, hand_type),
         };
     }
 
-    fn score(cards: &str, hand_type: HandType) -> (HandType, u8, u8, u8, u8, u8) {
-        let cards: Vec<u8> = cards.chars().into_iter().map(HandPart2::card_to_int).collect();
-        return (hand_type, cards[0], cards[1], cards[2], cards[3], cards[4]);
+    fn score(cards: &str, hand_type: HandType) -> (HandType, Vec<u8>) {
+        return (hand_type, cards.chars().map(HandPart2::card_to_int).collect());
     }
 }
 
@@ -134,18 +171,74 @@ enum HandType {
 
 #[cfg(test)]
 mod test_day07 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    const CARDS: &str = "23456789TJQKA";
+
+    /// Hands drawn from a random subset of the cards, so pairs, full houses and the like come up often.
+    fn hand() -> impl Strategy<Value = String> {
+        return prop::sample::subsequence(CARDS.chars().collect::<Vec<char>>(), 1..=CARDS.len())
+            .prop_flat_map(|cards| prop::collection::vec(prop::sample::select(cards), 5))
+            .prop_map(|cards| cards.into_iter().collect());
+    }
+
+    /// Classifies the hand by how often each card occurs, most common first.
+    fn hand_type_oracle(cards: &str) -> HandType {
+        let counts: Vec<usize> = cards.chars().counts().into_values().sorted().rev().collect();
+        return match counts[..] {
+            [5] => HandType::FiveOfAKind,
+            [4, 1] => HandType::FourOfAKind,
+            [3, 2] => HandType::FullHouse,
+            [3, 1, 1] => HandType::ThreeOfAKind,
+            [2, 2, 1] => HandType::TwoPair,
+            [2, 1, 1, 1] => HandType::OnePair,
+            _ => HandType::HighCard,
+        };
+    }
+
+    /// Tries every card for every joker and keeps the best hand. Cards that are not in the hand all make the
+    /// same type, so only one of them is tried to keep hands full of jokers fast.
+    fn hand_type_with_jokers_oracle(cards: &str) -> HandType {
+        let absent = CARDS.chars().find(|c| *c != 'J' && !cards.contains(*c)).unwrap();
+        let replacements: Vec<char> = CARDS.chars().filter(|c| *c != 'J' && (cards.contains(*c) || *c == absent)).collect();
+        let options: Vec<Vec<char>> = cards.chars()
+            .map(|card| if card == 'J' { replacements.clone() } else { vec![card] })
+            .collect();
+        return options.into_iter()
+            .multi_cartesian_product()
+            .map(|cards| hand_type_oracle(&cards.into_iter().collect::<String>()))
+            .max()
+            .unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn test_cards_to_type_matches_oracle(cards in hand()) {
+            prop_assert_eq!(HandPart1::cards_to_type(&cards), hand_type_oracle(&cards));
+        }
+
+        #[test]
+        fn test_cards_to_type_with_jokers_matches_oracle(cards in hand()) {
+            prop_assert_eq!(HandPart2::cards_to_type(&cards), hand_type_with_jokers_oracle(&cards));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part1() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part1(input), 6440)
+        let input = &example!("example.txt");
+        assert_eq!(part1(input), Ok(6440))
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part2() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part2(input), 5905)
+        let input = &example!("example.txt");
+        assert_eq!(part2(input), Ok(5905))
     }
 
     #[test]
@@ -156,4 +249,18 @@ mod test_day07 {
         assert!(HandType::ThreeOfAKind > HandType::TwoPair);
         assert!(HandType::TwoPair > HandType::OnePair);
     }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(Day07::parse("32T3K 765\nT55X5 684").unwrap_err(), ParseError::new(2, 4, "X5", "invalid card"));
+        assert_eq!(Day07::parse("32T3 765").unwrap_err().message, "expected a hand of five cards");
+        assert_eq!(Day07::parse("32T3K").unwrap_err().message, "expected `<hand> <bid>`");
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = format!("32T3K 1\nT55J5 {}", usize::MAX / 2 + 1);
+        assert_eq!(part1(&input), Err(checked::overflow::<usize>()));
+        assert_eq!(part2(&format!("32T3K {}\nT55J5 1", usize::MAX - 1)), Err(checked::overflow::<usize>()));
+    }
 }
\ No newline at end of file
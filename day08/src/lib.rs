@@ -1,203 +1,429 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{self, line_ending, multispace1},
+    character::complete::{self, line_ending, multispace0, multispace1},
+    combinator::{eof, value},
     IResult,
     multi::{many1, separated_list1},
-    sequence::{delimited, separated_pair}
+    Parser,
+    sequence::{delimited, pair, separated_pair}
 };
 use nom::character::complete::alphanumeric1;
+use nom_supreme::error::ErrorTree;
+use nom_supreme::tag::complete::tag;
+use nom_supreme::ParserExt;
+use tracing::debug;
 
-pub fn part1(input: &str) -> usize {
-    let (input, instructions) = parse_instructions(input).expect("Valid instructions");
-    let (_, nodes) = parse_nodes(input).expect("Valid map");
+use aoc_common::parse::from_nom;
+use aoc_common::{ParseError, Solution, SolveError};
+use aoc_graph::{brent, bfs};
+use aoc_math::Congruence;
 
-    let mut cur = "AAA";
-    return instructions.iter().cycle().enumerate().find_map(|(index, instruction)| {
-        let (left, right) = nodes.get(cur).expect("invalid node");
-        cur = match instruction {
-            Instruction::Left(_) => left,
-            Instruction::Right(_) => right,
-        };
-        if cur == "ZZZ" { Some(index + 1) } else { None }
-    }).unwrap();
+pub struct Day08;
+
+#[derive(Debug)]
+pub struct Network<'a> {
+    instructions: Vec<Instruction>,
+    nodes: Nodes<'a>,
 }
 
-pub fn part2(input: &str) -> usize {
-    let (input, instructions) = parse_instructions(input).expect("Valid instructions");
-    let (_, nodes) = parse_nodes(input).expect("Valid map");
-
-    let mut ghosts: Vec<Ghost> = nodes.keys()
-        .filter(|name| name.ends_with("A"))
-        .map(|node| Ghost::new(node))
-        .collect();
-    let _ = instructions.iter().cycle().enumerate().take_while(|(index, instruction)| {
-        ghosts.iter_mut()
-            .filter(|ghost| ghost.loop_info.is_none())
-            .for_each(|ghost| {
-                let (left, right) = nodes.get(ghost.current).unwrap();
-                let new_node = match instruction {
-                    Instruction::Left(_) => left,
-                    Instruction::Right(_) => right,
-                };
-                ghost.visit(new_node, instruction, index + 1);
-            });
-        return ghosts.iter().any(|ghost| ghost.loop_info.is_none());
-    }).last();
-    let loops: Vec<LoopInfo> = ghosts.iter().map(|ghost| ghost.loop_info.unwrap()).collect();
-    if !loops.iter().all(|l| l.start == l.period) {
-        panic!("Can only handle all loops starting at 0");
-    }
-    return lcm(&loops.iter().map(|l| l.period).collect::<Vec<usize>>());
+/// Every node with the nodes reached by going left and right from it.
+type Nodes<'a> = BTreeMap<&'a str, (&'a str, &'a str)>;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    type Params = ();
+    type Input<'a> = Network<'a>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Network<'_>, ParseError> {
+        let (_, (instructions, nodes)) = pair(parse_instructions, parse_nodes)(input)
+            .map_err(|err| from_nom(input, err))?;
+        let unknown = nodes.values()
+            .flat_map(|(left, right)| [left, right])
+            .find(|name| !nodes.contains_key(*name));
+        if let Some(name) = unknown {
+            return Err(ParseError::locate(input, name, "unknown node"));
+        }
+        return Ok(Network { instructions, nodes });
+    }
+
+    fn part1(network: &Network, _: &()) -> Result<usize, SolveError> {
+        if !network.nodes.contains_key("AAA") {
+            return Err(SolveError::no_answer("there is no node AAA to start from"));
+        }
+        let path = bfs(("AAA", 0), |&state| [network.step(state)], |&(node, _)| node == "ZZZ");
+        return path.map(|path| path.len() - 1).ok_or_else(|| SolveError::no_answer("ZZZ cannot be reached from AAA"));
+    }
+
+    fn part2(network: &Network, _: &()) -> Result<usize, SolveError> {
+        let loops: Vec<LoopInfo> = network.nodes.keys()
+            .filter(|name| name.ends_with('A'))
+            .map(|node| network.find_loop(node))
+            .collect();
+        if loops.is_empty() {
+            return Err(SolveError::no_answer("there are no nodes ending in A to start from"));
+        }
+        return first_common_end(&loops, SEARCH_LIMIT);
+    }
 }
 
-pub fn lcm(nums: &[usize]) -> usize {
-    if nums.len() == 1 {
-        return nums[0];
+impl<'a> Network<'a> {
+    /// Where a ghost at `node`, about to follow the instruction at `index`, ends up, and the index of the
+    /// instruction it follows next. The parser made sure every node leads to known nodes.
+    fn step(self: &Network<'a>, (node, index): (&'a str, usize)) -> (&'a str, usize) {
+        let (left, right) = self.nodes[node];
+        let next = match self.instructions[index] {
+            Instruction::Left => left,
+            Instruction::Right => right,
+        };
+        return (next, (index + 1) % self.instructions.len());
+    }
+
+    /// The loop a ghost starting at `node` ends up walking, with the steps at which it is at a node ending
+    /// in `Z` up to the first time round the loop.
+    fn find_loop(self: &Network<'a>, node: &'a str) -> LoopInfo {
+        let (prefix, period) = brent((node, 0), |&state| self.step(state));
+        let ends = std::iter::successors(Some((node, 0)), |&state| Some(self.step(state)))
+            .take(prefix + period)
+            .enumerate()
+            .filter(|(_, (node, _))| node.ends_with('Z'))
+            .map(|(step, _)| step)
+            .collect::<Vec<usize>>();
+        debug!(start = node, prefix, period, ?ends, "ghost loop");
+        return LoopInfo { prefix, period, ends };
     }
-    let a = nums[0];
-    let b = lcm(&nums[1..]);
-    a * b / gcd(a, b)
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        return a;
+/// How many combinations of the ends of the ghosts are tried at most before giving up.
+const SEARCH_LIMIT: usize = 1_000_000;
+
+/// The first step, after setting off, at which every ghost is at an end at once. Fails when no such step
+/// turns up within `limit` combinations of the ends of the ghosts.
+fn first_common_end(loops: &[LoopInfo], limit: usize) -> Result<usize, SolveError> {
+    // Until every ghost is in its loop, simply check step by step.
+    let prefix = loops.iter().map(|l| l.prefix).max().unwrap_or(0);
+    if let Some(step) = (1..prefix).find(|step| loops.iter().all(|l| l.is_end(*step))) {
+        return Ok(step);
     }
-    gcd(b, a % b)
+    // From then on every ghost is at an end at the steps congruent to one of its ends in the loop. Ghosts
+    // at an end all round their loop rule out nothing, the others are combined fewest ends first.
+    let mut loops: Vec<&LoopInfo> = loops.iter().filter(|l| l.loop_ends().count() < l.period).collect();
+    loops.sort_by_key(|l| l.loop_ends().count());
+    let first = prefix.max(1) as i128;
+    let mut search = Search { first, best: None, budget: limit };
+    search.combine(Congruence::new(0, 1), &loops)?;
+    debug!(prefix, best = ?search.best, tried = limit - search.budget, "common ends in the loops");
+    return search.best
+        .and_then(|step| usize::try_from(step).ok())
+        .ok_or_else(|| SolveError::no_answer("the ghosts are never at an end at the same time"));
+}
+
+/// A depth-first search for the first common end, combining the ends of one ghost at a time.
+struct Search {
+    first: i128,
+    /// The first common end found so far.
+    best: Option<i128>,
+    /// How many more combinations may be tried.
+    budget: usize,
 }
 
-fn parse_instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
-    let (input, raw_instructions): (&str, Vec<char>) = many1(
+impl Search {
+    /// Looks for steps congruent to `congruence` and to an end of each of `loops`. As every further
+    /// ghost only rules steps out, a congruence whose first step is no better than the best is dropped.
+    fn combine(self: &mut Search, congruence: Congruence, loops: &[&LoopInfo]) -> Result<(), SolveError> {
+        let step = self.first.saturating_add((congruence.residue - self.first).rem_euclid(congruence.modulus));
+        if self.best.is_some_and(|best| step >= best) {
+            return Ok(());
+        }
+        let Some((l, rest)) = loops.split_first() else {
+            self.best = Some(step);
+            return Ok(());
+        };
+        for end in l.loop_ends() {
+            self.budget = self.budget.checked_sub(1)
+                .ok_or_else(|| SolveError::no_answer("the ghosts' ends have too many combinations to search"))?;
+            if let Some(combined) = congruence.combine(end) {
+                self.combine(combined, rest)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day08::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day08::solve_part2(input)
+}
+
+type ParseResult<'a, T> = IResult<&'a str, T, ErrorTree<&'a str>>;
+
+fn parse_instructions(input: &str) -> ParseResult<'_, Vec<Instruction>> {
+    return many1(
         alt((
-            complete::char('L'),
-            complete::char('R'),
+            value(Instruction::Left, complete::char('L')),
+            value(Instruction::Right, complete::char('R')),
         ))
-    )(input)?;
-    return Ok((
-        input,
-        raw_instructions.iter()
-            .enumerate()
-            .map(|(i, c)| match c {
-                'L' => Instruction::Left(i),
-                'R' => Instruction::Right(i),
-                _ => panic!("Should not happen (unrecognized instruction: {})", c)
-            })
-            .collect()
-    ));
+    ).context("instructions").parse(input);
+}
+
+fn parse_node(input: &str) -> ParseResult<'_, (&str, (&str, &str))> {
+    // Once a line starts with a name, the rest of it has to describe that node.
+    return separated_pair(
+        alphanumeric1,
+        tag(" = ").cut(),
+        delimited(
+            complete::char('('),
+            separated_pair(alphanumeric1, tag(", "), alphanumeric1),
+            complete::char(')')
+        ).cut()
+    ).context("node").parse(input);
 }
 
-fn parse_nodes(input: &str) -> IResult<&str, BTreeMap<&str, (&str, &str)>> {
+fn parse_nodes(input: &str) -> ParseResult<'_, Nodes<'_>> {
     let (input, _) = multispace1(input)?;
-    let (input, entries): (&str, Vec<(&str, (&str, &str))>) = separated_list1(
-        line_ending,
-        separated_pair(
-            alphanumeric1,
-            tag(" = "),
-            delimited(
-                complete::char('('),
-                separated_pair(alphanumeric1, tag(", "), alphanumeric1),
-                complete::char(')')
-            )
-        )
-    )(input)?;
-    debug_assert!(input.is_empty());
+    let (input, entries) = separated_list1(line_ending, parse_node)(input)?;
+    let (input, _) = pair(multispace0, eof).context("nodes").parse(input)?;
     return Ok((input, BTreeMap::from_iter(entries)));
 }
 
-#[derive(Debug, PartialOrd, PartialEq)]
-enum Instruction { Left(usize), Right(usize) }
-impl Instruction {
-    pub fn to_string(self: &Instruction) -> String {
-        match self {
-            Instruction::Left(x) => format!("(L@[{}])", x),
-            Instruction::Right(x) => format!("(R@[{}])", x),
-        }
-    }
-}
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Instruction { Left, Right }
 
-#[derive(Debug, Copy, Clone)]
+/// A ghost walks `prefix` steps before going round a loop of `period` steps forever.
+#[derive(Debug, Clone, PartialEq)]
 struct LoopInfo {
-    start: usize,
+    prefix: usize,
     period: usize,
+    /// The steps at which the ghost is at an end, up to the first time round the loop.
+    ends: Vec<usize>,
 }
 
-#[derive(Debug)]
-struct Ghost<'a> {
-    current: &'a str,
-    visited: HashMap<String, usize>,
-    loop_info: Option<LoopInfo>,
-}
-impl <'a>Ghost<'a> {
-    pub fn new(node: &str) -> Ghost {
-        Ghost {
-            current: node,
-            visited: HashMap::new(),
-            loop_info: None,
-        }
+impl LoopInfo {
+    fn is_end(self: &LoopInfo, step: usize) -> bool {
+        let step = if step < self.prefix { step } else { self.prefix + (step - self.prefix) % self.period };
+        return self.ends.contains(&step);
     }
 
-    pub fn visit(self: &mut Ghost<'a>, node: &'a str, after: &Instruction, index: usize) {
-        let key = after.to_string() + "-" + node;
-        self.current = node;
-        if node.ends_with("Z") {
-            if let Some(seen_index) = self.visited.get(&key) {
-                self.loop_info = Some(LoopInfo { start: *seen_index, period: index-seen_index});
-            } else {
-                self.visited.insert(key, index);
-            }
-        }
+    /// The ends in the loop, as the steps that are congruent to them.
+    fn loop_ends(self: &LoopInfo) -> impl Iterator<Item = Congruence> + '_ {
+        return self.ends.iter()
+            .filter(|end| **end >= self.prefix)
+            .map(|end| Congruence::new(*end as i128, self.period as i128));
     }
 }
 
 #[cfg(test)]
 mod test_day08 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    fn render(instructions: &str, nodes: &[(String, String, String)]) -> String {
+        let nodes = nodes.iter().map(|(name, left, right)| format!("{name} = ({left}, {right})")).collect::<Vec<_>>();
+        return format!("{instructions}\n\n{}", nodes.join("\n"));
+    }
+
+    /// Random networks from `AAA` to `ZZZ`, which may well never reach `ZZZ`.
+    fn network() -> impl Strategy<Value = (String, Vec<(String, String, String)>)> {
+        let names = (0..4usize).prop_map(|middle| {
+            let middle = ["BBB", "CCC", "DDD", "EEE"][..middle].iter().map(|name| name.to_string());
+            return std::iter::once("AAA".to_string()).chain(middle).chain(std::iter::once("ZZZ".to_string())).collect::<Vec<_>>();
+        });
+        return ("[LR]{1,6}", names).prop_flat_map(|(instructions, names)| {
+            let targets = prop::collection::vec((0..names.len(), 0..names.len()), names.len());
+            return (Just(instructions), targets.prop_map(move |targets| {
+                names.iter().zip(targets)
+                    .map(|(name, (left, right))| (name.clone(), names[left].clone(), names[right].clone()))
+                    .collect()
+            }));
+        });
+    }
+
+    /// Follows the instructions one step at a time, from every start at once, until all of them are at
+    /// an end. Gives up once every combination of node and instruction has been seen.
+    fn steps_oracle(instructions: &str, nodes: &[(String, String, String)], starts: Vec<&str>, is_end: impl Fn(&str) -> bool) -> Option<usize> {
+        let mut current = starts;
+        let limit = instructions.len() * nodes.len().pow(current.len() as u32);
+        for (step, instruction) in instructions.chars().cycle().enumerate().take(limit) {
+            current = current.iter()
+                .map(|name| {
+                    let (_, left, right) = nodes.iter().find(|(node, _, _)| node == name).unwrap();
+                    return if instruction == 'L' { left.as_str() } else { right.as_str() };
+                })
+                .collect();
+            if current.iter().all(|name| is_end(name)) {
+                return Some(step + 1);
+            }
+        }
+        return None;
+    }
+
+    /// Networks in which every ghost walks from its start into a loop whose length is a multiple of the
+    /// number of instructions, and ends at the last node of that loop, the way the puzzle inputs are made.
+    fn ghost_network() -> impl Strategy<Value = (String, Vec<(String, String, String)>)> {
+        return ("[LR]{1,3}", prop::collection::vec(1..5usize, 1..4)).prop_map(|(instructions, loops)| {
+            let mut nodes = vec![];
+            for (ghost, multiple) in loops.into_iter().enumerate() {
+                let length = multiple * instructions.len();
+                let name = |index: usize| if index == length - 1 { format!("{ghost}ZZ") } else { format!("{ghost}{index:02}") };
+                nodes.push((format!("{ghost}AA"), name(0), name(0)));
+                nodes.extend((0..length).map(|index| (name(index), name((index + 1) % length), name((index + 1) % length))));
+            }
+            return (instructions, nodes);
+        });
+    }
+
+    /// Random networks with up to three ghosts, whose loops may start anywhere and pass any number of ends.
+    fn any_ghost_network() -> impl Strategy<Value = (String, Vec<(String, String, String)>)> {
+        let names = (1..4usize, 0..3usize, 1..3usize).prop_map(|(starts, middle, ends)| {
+            let starts = (0..starts).map(|index| format!("{index}AA"));
+            let middle = (0..middle).map(|index| format!("{index}BB"));
+            let ends = (0..ends).map(|index| format!("{index}ZZ"));
+            return starts.chain(middle).chain(ends).collect::<Vec<_>>();
+        });
+        return ("[LR]{1,4}", names).prop_flat_map(|(instructions, names)| {
+            let targets = prop::collection::vec((0..names.len(), 0..names.len()), names.len());
+            return (Just(instructions), targets.prop_map(move |targets| {
+                names.iter().zip(targets)
+                    .map(|(name, (left, right))| (name.clone(), names[left].clone(), names[right].clone()))
+                    .collect()
+            }));
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn test_part1_matches_oracle((instructions, nodes) in network()) {
+            let expected = steps_oracle(&instructions, &nodes, vec!["AAA"], |name| name == "ZZZ");
+            prop_assume!(expected.is_some());
+            prop_assert_eq!(part1(&render(&instructions, &nodes)).ok(), expected);
+        }
+
+        #[test]
+        fn test_part2_matches_oracle((instructions, nodes) in ghost_network()) {
+            let starts = nodes.iter().map(|(name, _, _)| name.as_str()).filter(|name| name.ends_with('A')).collect();
+            let expected = steps_oracle(&instructions, &nodes, starts, |name| name.ends_with('Z'));
+            prop_assert_eq!(part2(&render(&instructions, &nodes)).ok(), expected);
+        }
+
+        #[test]
+        fn test_part2_with_any_offsets_matches_oracle((instructions, nodes) in any_ghost_network()) {
+            let starts = nodes.iter().map(|(name, _, _)| name.as_str()).filter(|name| name.ends_with('A')).collect();
+            let expected = steps_oracle(&instructions, &nodes, starts, |name| name.ends_with('Z'));
+            prop_assume!(expected.is_some());
+            prop_assert_eq!(part2(&render(&instructions, &nodes)).ok(), expected);
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example1.txt, run with --include-ignored"]
     fn test_part1_example1() {
-        let example1 = include_str!("../resources/example1.txt");
-        assert_eq!(part1(example1), 2);
+        let example1 = &example!("example1.txt");
+        assert_eq!(part1(example1), Ok(2));
     }
     #[test]
+    #[ignore = "needs resources/example2.txt, run with --include-ignored"]
     fn test_part1_example2() {
-        let example2 = include_str!("../resources/example2.txt");
-        assert_eq!(part1(example2), 6);
+        let example2 = &example!("example2.txt");
+        assert_eq!(part1(example2), Ok(6));
     }
 
-    // Doesn't work with chosen method.
-    // #[test]
-    // fn test_part2() {
-    //     let example3 = include_str!("../resources/example3.txt");
-    //     assert_eq!(part2(example3), 6);
-    // }
+    #[test]
+    #[ignore = "needs resources/example3.txt, run with --include-ignored"]
+    fn test_part2() {
+        let example3 = &example!("example3.txt");
+        assert_eq!(part2(example3), Ok(6));
+    }
+
+    #[test]
+    fn test_first_common_end() {
+        // Ends at 3, 8, 13, .. and at 1, then at 4, 6, 8, ..
+        let a = LoopInfo { prefix: 1, period: 5, ends: vec![3] };
+        let b = LoopInfo { prefix: 4, period: 2, ends: vec![1, 4] };
+        assert_eq!(first_common_end(&[a.clone(), b], SEARCH_LIMIT), Ok(8));
+        let c = LoopInfo { prefix: 0, period: 10, ends: vec![1, 2] };
+        assert_eq!(first_common_end(&[a, c], SEARCH_LIMIT), Err(SolveError::no_answer("the ghosts are never at an end at the same time")));
+        // The ends of `d` and `e` meet at 81 of their 810 combinations, none of which meets an end of `g`.
+        let d = LoopInfo { prefix: 0, period: 10, ends: (1..10).collect() };
+        let e = LoopInfo { prefix: 0, period: 100, ends: (0..100).filter(|end| end % 10 != 0).collect() };
+        let g = LoopInfo { prefix: 0, period: 1000, ends: (0..1000).filter(|end| end % 10 == 0).collect() };
+        let loops = [d, e, g];
+        assert_eq!(first_common_end(&loops, SEARCH_LIMIT), Err(SolveError::no_answer("the ghosts are never at an end at the same time")));
+        assert_eq!(first_common_end(&loops, 1000), Err(SolveError::no_answer("the ghosts' ends have too many combinations to search")));
+    }
+
+    #[test]
+    fn test_many_ghosts() {
+        // Ghosts going round loops of only ends, of a prime length each, are at an end on every step.
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+        let nodes: Vec<String> = primes.iter().enumerate()
+            .flat_map(|(ghost, prime)| {
+                let start = format!("{ghost}GA = ({ghost}G0Z, {ghost}G0Z)");
+                let ends = (0..*prime).map(move |end| format!("{ghost}G{end}Z = ({ghost}G{0}Z, {ghost}G{0}Z)", (end + 1) % prime));
+                return std::iter::once(start).chain(ends);
+            })
+            .collect();
+        assert_eq!(part2(&format!("L\n\n{}", nodes.join("\n"))), Ok(1));
+        // With every other step of the loops an end, they are all at an end at the odd steps.
+        let nodes: Vec<String> = primes.iter().enumerate()
+            .flat_map(|(ghost, prime)| {
+                let start = format!("{ghost}GA = ({ghost}G0Z, {ghost}G0Z)");
+                let ends = (0..*prime).flat_map(move |end| [
+                    format!("{ghost}G{end}Z = ({ghost}G{end}B, {ghost}G{end}B)"),
+                    format!("{ghost}G{end}B = ({ghost}G{0}Z, {ghost}G{0}Z)", (end + 1) % prime),
+                ]);
+                return std::iter::once(start).chain(ends);
+            })
+            .collect();
+        assert_eq!(part2(&format!("L\n\n{}", nodes.join("\n"))), Ok(1));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = Day08::parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(err.to_string(), "line 4, column 16: expected ')' in node");
+        let err = Day08::parse("LLR\n\nAAA = (BBB, BBB)\nBBB -> (AAA, ZZZ)").unwrap_err();
+        assert_eq!(err.to_string(), "line 4, column 4: expected \" = \" in node (found ` -> (AAA, ZZZ)`)");
+        let err = Day08::parse("LXR\n\nAAA = (BBB, BBB)").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 2));
+        let err = Day08::parse("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)").unwrap_err();
+        assert_eq!(err, ParseError::new(4, 13, "ZZZ", "unknown node"));
+    }
+
+    #[test]
+    fn test_no_answer() {
+        let err = part1("LR\n\n11A = (11Z, 11Z)\n11Z = (11Z, 11Z)").unwrap_err();
+        assert_eq!(err, SolveError::no_answer("there is no node AAA to start from"));
+        let err = part1("LR\n\nAAA = (AAA, BBB)\nBBB = (AAA, AAA)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!(err, SolveError::no_answer("ZZZ cannot be reached from AAA"));
+        let err = part2("LR\n\nAAA = (AAA, AAA)").unwrap_err();
+        assert_eq!(err, SolveError::no_answer("the ghosts are never at an end at the same time"));
+        let err = part2("LR\n\nBBB = (BBB, BBB)").unwrap_err();
+        assert_eq!(err, SolveError::no_answer("there are no nodes ending in A to start from"));
+    }
 
     #[test]
     fn test_loop_detection() {
-        let nodes = BTreeMap::from([
-           ("A", ("B", "C")),
-           ("B", ("C", "Z")),
-           ("C", ("B", "Z")),
-           ("Z", ("B", "C")),
-        ]);
-        let instructions = vec![Instruction::Right(0), Instruction::Left(1), Instruction::Left(2)];
-        assert_ne!(instructions[1], instructions[2]);
-        assert_eq!(instructions[1], instructions[1]);
-        assert_eq!(Instruction::Left(3), Instruction::Left(3));
-        let mut ghost = Ghost::new("A");
-        let _: Option<(usize, &Instruction)> = instructions.iter().cycle().enumerate().take_while(|(index, instruction)| {
-            let (left, right) = nodes.get(ghost.current).unwrap();
-            let new_node = match instruction {
-                Instruction::Left(_) => left,
-                Instruction::Right(_) => right,
-            };
-            ghost.visit(new_node, instruction, index + 1);
-            return ghost.loop_info.is_none();
-        }).last();
-        let loop_info = ghost.loop_info.unwrap();
-        assert_eq!(loop_info.start, 4);
-        assert_eq!(loop_info.period, 3);
-    }
-}
\ No newline at end of file
+        let network = Network {
+            instructions: vec![Instruction::Right, Instruction::Left, Instruction::Left],
+            nodes: BTreeMap::from([
+                ("A", ("B", "C")),
+                ("B", ("C", "Z")),
+                ("C", ("B", "Z")),
+                ("Z", ("B", "C")),
+            ]),
+        };
+        assert_eq!(network.step(("A", 0)), ("C", 1));
+        let loop_info = network.find_loop("A");
+        assert_eq!(loop_info, LoopInfo { prefix: 2, period: 3, ends: vec![4] });
+        assert!(loop_info.is_end(7));
+        assert!(!loop_info.is_end(8));
+    }
+}
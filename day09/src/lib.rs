@@ -1,54 +1,138 @@
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> isize {
-    return parse_sequences(input).iter()
-        .map(|sequence| get_next(sequence))
-        .sum();
+use aoc_common::parse::parse_number;
+use aoc_common::{checked, parallel, ParseError, Solution, SolveError};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    type Params = ();
+    type Input<'a> = Vec<Vec<isize>>;
+    type Answer = isize;
+
+    fn parse(input: &str) -> Result<Vec<Vec<isize>>, ParseError> {
+        parse_sequences(input)
+    }
+
+    fn part1(sequences: &Vec<Vec<isize>>, _: &()) -> Result<isize, SolveError> {
+        return checked::sum(sequences.iter()
+            .map(|sequence| get_next(sequence))
+            .collect::<Result<Vec<isize>, SolveError>>()?);
+    }
+
+    fn part2(sequences: &Vec<Vec<isize>>, _: &()) -> Result<isize, SolveError> {
+        return checked::sum(sequences.iter()
+            .map(|sequence| get_prev(sequence))
+            .collect::<Result<Vec<isize>, SolveError>>()?);
+    }
+}
+
+pub fn part1(input: &str) -> Result<isize, SolveError> {
+    Day09::solve_part1(input)
 }
 
-pub fn part2(input: &str) -> isize {
-    return parse_sequences(input).iter()
-        .map(|sequence| get_prev(sequence))
-        .sum();
+pub fn part2(input: &str) -> Result<isize, SolveError> {
+    Day09::solve_part2(input)
 }
 
-fn parse_sequences(input: &str) -> Vec<Vec<isize>> {
-    let sequences: Vec<Vec<isize>> = input.lines().map(|line| {
-        line.split_whitespace().map(|number| number.parse::<isize>().unwrap()).collect()
-    }).collect();
-    sequences
+fn parse_sequences(input: &str) -> Result<Vec<Vec<isize>>, ParseError> {
+    return parallel::parse_lines(input, |line| {
+        line.split_whitespace().map(|number| parse_number::<isize>(line, number)).collect()
+    });
 }
 
-fn get_next(sequence: &Vec<isize>) -> isize {
-    if sequence.iter().all(|x| *x == 0) { return 0; }
-    let derivative = get_next(&sequence.iter().tuple_windows().map(|(x1, x2)| x2 - x1).collect());
-    return sequence.last().unwrap() + derivative;
+fn get_next(sequence: &[isize]) -> Result<isize, SolveError> {
+    return match sequence {
+        [.., last] if sequence.iter().any(|x| *x != 0) => checked::add(*last, get_next(&derivative(sequence)?)?),
+        _ => Ok(0),
+    };
 }
 
-fn get_prev(sequence: &Vec<isize>) -> isize {
-    if sequence.iter().all(|x| *x == 0) { return 0; }
-    let derivative = get_prev(&sequence.iter().tuple_windows().map(|(x1, x2)| x2 - x1).collect());
-    return sequence.first().unwrap() - derivative;
+fn get_prev(sequence: &[isize]) -> Result<isize, SolveError> {
+    return match sequence {
+        [first, ..] if sequence.iter().any(|x| *x != 0) => checked::sub(*first, get_prev(&derivative(sequence)?)?),
+        _ => Ok(0),
+    };
+}
+
+/// The differences between the consecutive values of `sequence`.
+fn derivative(sequence: &[isize]) -> Result<Vec<isize>, SolveError> {
+    return sequence.iter().tuple_windows().map(|(x1, x2)| checked::sub(*x2, *x1)).collect();
 }
 
 #[cfg(test)]
 mod test_day09 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    /// A polynomial of a degree lower than the length of the sequence taken from it, as its coefficients.
+    fn polynomial() -> impl Strategy<Value = (Vec<isize>, usize)> {
+        return (prop::collection::vec(-5..=5isize, 1..5), 0..10usize)
+            .prop_map(|(coefficients, extra)| {
+                let length = coefficients.len() + extra;
+                return (coefficients, length);
+            });
+    }
+
+    fn evaluate(coefficients: &[isize], x: isize) -> isize {
+        return coefficients.iter().rev().fold(0, |value, coefficient| value * x + coefficient);
+    }
+
+    proptest! {
+        #[test]
+        fn test_get_next_and_prev_match_polynomial((coefficients, length) in polynomial()) {
+            let sequence: Vec<isize> = (0..length as isize).map(|x| evaluate(&coefficients, x)).collect();
+            prop_assert_eq!(get_next(&sequence), Ok(evaluate(&coefficients, length as isize)));
+            prop_assert_eq!(get_prev(&sequence), Ok(evaluate(&coefficients, -1)));
+        }
+
+        #[test]
+        fn test_parts_match_polynomials(polynomials in prop::collection::vec(polynomial(), 1..6)) {
+            let input = polynomials.iter()
+                .map(|(coefficients, length)| (0..*length as isize).map(|x| evaluate(coefficients, x).to_string()).collect::<Vec<_>>().join(" "))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let next: isize = polynomials.iter().map(|(coefficients, length)| evaluate(coefficients, *length as isize)).sum();
+            let prev: isize = polynomials.iter().map(|(coefficients, _)| evaluate(coefficients, -1)).sum();
+            prop_assert_eq!(part1(&input), Ok(next));
+            prop_assert_eq!(part2(&input), Ok(prev));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part1() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part1(input), 114);
+        let input = &example!("example.txt");
+        assert_eq!(part1(input), Ok(114));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part2() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part2(input), 2);
+        let input = &example!("example.txt");
+        assert_eq!(part2(input), Ok(2));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse_sequences("0 3 6\n1 -3 x4").unwrap_err(), ParseError::new(2, 6, "x4", "expected a number"));
     }
 
     #[test]
     fn test_derivative() {
-        assert_eq!(get_next(&vec![0]), 0);
+        assert_eq!(get_next(&[0]), Ok(0));
+        assert_eq!(get_next(&[]), Ok(0));
+        assert_eq!(get_prev(&[7]), Ok(7));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(part1(&format!("{} 0", isize::MIN)), Err(checked::overflow::<isize>()));
+        assert_eq!(part2(&format!("{} 0 {}", isize::MAX, isize::MAX)), Err(checked::overflow::<isize>()));
+        assert_eq!(part1(&format!("{0} {0}\n{0} {0}", isize::MAX)), Err(checked::overflow::<isize>()));
     }
 }
\ No newline at end of file
@@ -1,13 +1,63 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
-use glam::IVec2;
+use aoc_common::{ParseError, Solution, SolveError};
+use aoc_graph::dfs_reach;
+use aoc_grid::{Grid, Point};
 
-pub fn part1(input: &str) -> usize {
-    let matrix = parse(input);
-    let length = matrix.into_iter().enumerate().find_map(|(idx, (_, pipe))| {
-        return if pipe == Pipe::Start { Some(idx + 1) } else { None };
-    }).expect("A loop");
-    return length / 2;
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    type Params = ();
+    type Input<'a> = Matrix;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Matrix, ParseError> {
+        parse(input)
+    }
+
+    fn part1(matrix: &Matrix, _: &()) -> Result<usize, SolveError> {
+        return Ok(matrix.pipe_loop()?.len() / 2);
+    }
+
+    fn part2(matrix: &Matrix, _: &()) -> Result<usize, SolveError> {
+        let pipe_loop = matrix.pipe_loop()?;
+        let potential_pipes = [Pipe::Horizontal, Pipe::Vertical, Pipe::NE, Pipe::NW, Pipe::SW, Pipe::SE];
+        let start_pipe = potential_pipes.iter()
+            .find(|potential_pipe| {
+                matrix.connections(matrix.start).iter()
+                    .all(|neighbour| matrix.is_connected_with_supplied_pipe(matrix.start, *neighbour, **potential_pipe))
+            })
+            .ok_or_else(not_a_loop)?;
+        let pipe: HashMap<Point, Pipe> = pipe_loop.into_iter()
+            .map(|point| (point, if point == matrix.start { *start_pipe } else { matrix.get(point) }))
+            .collect();
+        return Ok((0..matrix.cells.height()).fold(0, |inside_cells, y| {
+            let inside_for_row = (0..matrix.cells.width()).fold((0, Side::Out), |(inside_cells_row, side), x| {
+                let coord = Point::new(x as i32, y as i32);
+                if let Some(pipe_cell) = pipe.get(&coord) {
+                    return match pipe_cell {
+                        Pipe::Start | Pipe::Vertical | Pipe::NE | Pipe::NW => (inside_cells_row, side.opposite()),
+                        _ => (inside_cells_row, side),
+                    };
+                }
+                return match side {
+                    Side::Out => (inside_cells_row, side),
+                    Side::In => (inside_cells_row + 1, side),
+                };
+            }).0;
+            return inside_cells + inside_for_row;
+        }));
+    }
+}
+
+fn not_a_loop() -> SolveError {
+    SolveError::no_answer("the start tile is not on a loop")
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day10::solve_part1(input)
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -21,168 +71,83 @@ impl Side {
     }
 }
 
-pub fn part2(input: &str) -> usize {
-    let matrix = parse(input);
-    let mut pipe: Vec<(IVec2, Pipe)> = matrix.clone().into_iter()
-        .take_while(|(_, pipe)| pipe != &Pipe::Start)
-        .collect();
-    let first_neighbour = pipe.first().unwrap().0;
-    let last_neighbour = pipe.last().unwrap().0;
-    let potential_pipes = vec![Pipe::Horizontal, Pipe::Vertical, Pipe::NE, Pipe::NW, Pipe::SW, Pipe::SE];
-    let start_pipe = potential_pipes.iter()
-        .find(|potential_pipe| {
-            matrix.is_connected_with_supplied_pipe(matrix.start, first_neighbour, **potential_pipe)
-                && matrix.is_connected_with_supplied_pipe(matrix.start, last_neighbour, **potential_pipe)
-        })
-        .unwrap();
-    pipe.push((matrix.start, *start_pipe));
-    let pipe: HashMap<IVec2, Pipe> = pipe.into_iter().collect();
-    let (y_size, x_size) = matrix.dimensions();
-    return (0..y_size).into_iter().fold(0, |inside_cells, y| {
-        let inside_for_row = (0..x_size).into_iter().fold((0, Side::Out), |(inside_cells_row, side), x| {
-            let coord = IVec2::new(x as i32, y as i32);
-            if let Some(pipe_cell) = pipe.get(&coord) {
-                return match pipe_cell {
-                    Pipe::Start | Pipe::Vertical | Pipe::NE | Pipe::NW => (inside_cells_row, side.opposite()),
-                    _ => (inside_cells_row, side),
-                };
-            }
-            return match side {
-                Side::Out => (inside_cells_row, side),
-                Side::In => (inside_cells_row + 1, side),
-            };
-        }).0;
-        return inside_cells + inside_for_row;
-    });
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day10::solve_part2(input)
 }
 
-fn parse(input: &str) -> Matrix {
-    let mut start: Option<IVec2> = None;
-    let matrix: Vec<Vec<Pipe>> = input.lines().enumerate().map(|(y, line)| {
-        line.chars().enumerate().map(|(x, c)| match c {
-            '-' => Pipe::Horizontal,
-            '|' => Pipe::Vertical,
-            'L' => Pipe::NE,
-            'J' => Pipe::NW,
-            'F' => Pipe::SE,
-            '7' => Pipe::SW,
-            'S' => {
-                start = Some(IVec2 { x: x as i32, y: y as i32 });
-                Pipe::Start // TOdo fix
-            }
-            '.' => Pipe::Ground,
-            _ => panic!("HHHH"),
-        }).collect()
-    }).collect();
-    let start = start.unwrap();
-    return Matrix { cells: matrix, start };
-}
-
-fn neighbours_of(loc: IVec2) -> Vec<IVec2> {
-    let offsets = vec![IVec2 { x: -1, y: 0 }, IVec2 { x: 0, y: -1 }, IVec2 { x: 1, y: 0 }, IVec2 { x: 0, y: 1 }];
-    offsets.iter()
-        .map(|offset| *offset + loc)
-        .filter(|neighbour| !(neighbour.x < 0 || neighbour.y < 0))
-        .collect()
+fn parse(input: &str) -> Result<Matrix, ParseError> {
+    let cells = Grid::parse(input, |c| Pipe::from_char(c).ok_or("invalid pipe"))?;
+    let start = cells.position(|pipe| *pipe == Pipe::Start)
+        .ok_or_else(|| ParseError::locate(input, &input[input.len()..], "expected a start tile `S`"))?;
+    if let Some((other, _)) = cells.iter().filter(|(_, pipe)| **pipe == Pipe::Start).nth(1) {
+        return Err(ParseError::new(other.y as usize + 1, other.x as usize + 1, "S", "expected a single start tile `S`"));
+    }
+    return Ok(Matrix { cells, start });
 }
 
 #[derive(Debug, Clone)]
-struct Matrix {
-    cells: Vec<Vec<Pipe>>,
-    start: IVec2,
+pub struct Matrix {
+    cells: Grid<Pipe>,
+    start: Point,
 }
 
 impl Matrix {
-    pub fn get(self: &Matrix, loc: IVec2) -> Pipe {
-        if let Some(row) = self.cells.get(loc.y as usize) {
-            if let Some(cell) = row.get(loc.x as usize) {
-                return cell.clone()
-            }
+    pub fn get(self: &Matrix, loc: Point) -> Pipe {
+        return self.cells.get(loc).copied().unwrap_or(Pipe::Ground);
+    }
+
+    /// The points of the loop through the start tile, in the order of walking it starting east if possible.
+    /// Fails when the pipes from the start tile dead-end or branch off.
+    pub fn pipe_loop(self: &Matrix) -> Result<Vec<Point>, SolveError> {
+        let pipe_loop = dfs_reach(self.start, |&point| self.connections(point));
+        if pipe_loop.len() < 4 || pipe_loop.iter().any(|point| self.connections(*point).len() != 2) {
+            return Err(not_a_loop());
         }
-        return Pipe::Ground;
+        return Ok(pipe_loop);
+    }
+
+    /// The neighbours of `point` its pipe connects to.
+    fn connections(self: &Matrix, point: Point) -> Vec<Point> {
+        return self.cells.neighbours_4(point)
+            .filter(|neighbour| self.is_connected(point, *neighbour))
+            .collect();
     }
 
-    pub fn is_connected(self: &Matrix, cur: IVec2, neighbour: IVec2) -> bool {
+    pub fn is_connected(self: &Matrix, cur: Point, neighbour: Point) -> bool {
         self.is_connected_with_supplied_pipe(cur, neighbour, self.get(cur))
     }
 
-    pub fn is_connected_with_supplied_pipe(self: &Matrix, cur: IVec2, neighbour: IVec2, pipe_for_cur: Pipe) -> bool {
+    pub fn is_connected_with_supplied_pipe(self: &Matrix, cur: Point, neighbour: Point, pipe_for_cur: Pipe) -> bool {
         let dir_vec = neighbour - cur;
-        if dir_vec.length_squared() != 1 { return false; }
+        if dir_vec.manhattan_distance(Point::default()) != 1 { return false; }
         let cur = pipe_for_cur;
         let neighbour = self.get(neighbour);
         return match (cur, neighbour) {
             (
                 Pipe::Vertical | Pipe::NW | Pipe::NE | Pipe::Start,
                 Pipe::Vertical | Pipe::SW | Pipe::SE | Pipe::Start
-            ) if dir_vec == IVec2 { x: 0, y: -1 } => true, // North
+            ) if dir_vec == Point::NORTH => true,
             (
                 Pipe::Vertical | Pipe::SW | Pipe::SE | Pipe::Start,
                 Pipe::Vertical | Pipe::NW | Pipe::NE | Pipe::Start
-            ) if dir_vec == IVec2 { x: 0, y: 1 } => true, // South
+            ) if dir_vec == Point::SOUTH => true,
             (
                 Pipe::Horizontal | Pipe::NW | Pipe::SW | Pipe::Start,
                 Pipe::Horizontal | Pipe::NE | Pipe::SE | Pipe::Start
-            ) if dir_vec == IVec2 { x: -1, y: 0 } => true, // West
+            ) if dir_vec == Point::WEST => true,
             (
                 Pipe::Horizontal | Pipe::NE | Pipe::SE | Pipe::Start,
                 Pipe::Horizontal | Pipe::NW | Pipe::SW | Pipe::Start
-            ) if dir_vec == IVec2 { x: 1, y: 0 } => true, // East
+            ) if dir_vec == Point::EAST => true,
             _ => false,
         };
 
 
     }
-
-    pub fn dimensions(self: &Matrix) -> (usize, usize) {
-        return (self.cells.len(), self.cells[0].len());
-    }
-}
-
-impl IntoIterator for Matrix {
-    type Item = (IVec2, Pipe);
-    type IntoIter = MatrixIterator;
-
-    fn into_iter(self) -> Self::IntoIter {
-        let start = self.start;
-        MatrixIterator {
-            matrix: self,
-            current: Some(start),
-            last: None,
-        }
-    }
-}
-
-#[derive(Debug)]
-struct MatrixIterator {
-    matrix: Matrix,
-    last: Option<IVec2>,
-    current: Option<IVec2>,
-}
-
-impl Iterator for MatrixIterator {
-    type Item = (IVec2, Pipe);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let current = self.current?;
-        let new = neighbours_of(current)
-            .iter()
-            .filter(|neighbour| self.last.is_none() || neighbour != &&self.last.unwrap())
-            .map(|neighbour| (neighbour, self.matrix.get(*neighbour)))
-            .find_map(|(neighbour, pipe)| {
-                if self.matrix.is_connected(current, *neighbour) {
-                    Some((neighbour, pipe))
-                } else { None }
-            })
-            .map(|(point, pipe)| (point.clone(), pipe.clone()));
-        self.last = Some(current);
-        self.current = new.map(|(point, _)| point.clone());
-        return new;
-    }
 }
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Ord, Eq)]
-enum Pipe {
+pub enum Pipe {
     Horizontal,
     Vertical,
     NE,
@@ -193,63 +158,266 @@ enum Pipe {
     Ground,
 }
 
+const PIPES: [(char, Pipe); 8] = [
+    ('-', Pipe::Horizontal),
+    ('|', Pipe::Vertical),
+    ('L', Pipe::NE),
+    ('J', Pipe::NW),
+    ('F', Pipe::SE),
+    ('7', Pipe::SW),
+    ('S', Pipe::Start),
+    ('.', Pipe::Ground),
+];
+
+impl Pipe {
+    fn from_char(c: char) -> Option<Pipe> {
+        return PIPES.iter().find(|(symbol, _)| *symbol == c).map(|(_, pipe)| *pipe);
+    }
+}
+
+impl Display for Pipe {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (symbol, _) = PIPES.iter().find(|(_, pipe)| pipe == self).unwrap();
+        write!(f, "{symbol}")
+    }
+}
+
 
 #[cfg(test)]
 mod test_day10 {
-    use glam::IVec2;
+    use std::collections::{HashSet, VecDeque};
+
+    use proptest::prelude::*;
+
+    use aoc_common::example;
 
     use super::*;
 
+    const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+    fn openings(pipe: char) -> Vec<(i32, i32)> {
+        return match pipe {
+            '|' => vec![(0, -1), (0, 1)],
+            '-' => vec![(-1, 0), (1, 0)],
+            'L' => vec![(0, -1), (1, 0)],
+            'J' => vec![(0, -1), (-1, 0)],
+            '7' => vec![(0, 1), (-1, 0)],
+            'F' => vec![(0, 1), (1, 0)],
+            _ => vec![],
+        };
+    }
+
+    fn pipe_with_openings(mut wanted: Vec<(i32, i32)>) -> char {
+        wanted.sort();
+        return "|-LJ7F".chars()
+            .find(|pipe| {
+                let mut openings = openings(*pipe);
+                openings.sort();
+                return openings == wanted;
+            })
+            .unwrap();
+    }
+
+    /// The corners of the outline of a region that is convex along its columns: column `x` covers the rows
+    /// `tops[x]..bottoms[x]` and overlaps with the column before it, so the outline never touches itself.
+    /// Walking the outline one step at a time gives the tiles of a loop.
+    fn outline(columns: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let (mut tops, mut bottoms): (Vec<i32>, Vec<i32>) = (vec![], vec![]);
+        for (x, (a, b)) in columns.iter().enumerate() {
+            let (top, bottom) = match x {
+                0 => (*a.min(b), a.max(b) + 1),
+                _ => {
+                    let top = *a.min(&(bottoms[x - 1] - 1));
+                    (top, *b.max(&(top + 1)).max(&(tops[x - 1] + 1)))
+                }
+            };
+            tops.push(top);
+            bottoms.push(bottom);
+        }
+        let corners: Vec<(i32, i32)> = (0..tops.len())
+            .flat_map(|x| [(x as i32, tops[x]), (x as i32 + 1, tops[x])])
+            .chain((0..bottoms.len()).rev().flat_map(|x| [(x as i32 + 1, bottoms[x]), (x as i32, bottoms[x])]))
+            .collect();
+        let mut tiles: Vec<(i32, i32)> = vec![];
+        for (index, &(x, y)) in corners.iter().enumerate() {
+            let (next_x, next_y) = corners[(index + 1) % corners.len()];
+            let (mut x, mut y) = (x, y);
+            while (x, y) != (next_x, next_y) {
+                tiles.push((x, y));
+                x += (next_x - x).signum();
+                y += (next_y - y).signum();
+            }
+        }
+        return tiles;
+    }
+
+    /// Grids with a single loop through the start tile, surrounded by random pipes and ground. The pipes
+    /// around the start never point at it unless they are part of the loop, as in the puzzle inputs.
+    fn pipe_grid() -> impl Strategy<Value = String> {
+        let columns = prop::collection::vec((0..5i32, 0..5i32), 1..7);
+        let junk = prop::collection::vec(prop::sample::select(".|-LJ7F".chars().collect::<Vec<char>>()), 11 * 11);
+        return (columns, 0..3i32, 0..3i32, junk, any::<prop::sample::Index>()).prop_map(|(columns, offset, padding, junk, start)| {
+            let tiles: Vec<(i32, i32)> = outline(&columns).into_iter().map(|(x, y)| (x + offset, y + offset)).collect();
+            let width = tiles.iter().map(|(x, _)| x).max().unwrap() + 1 + padding;
+            let height = tiles.iter().map(|(_, y)| y).max().unwrap() + 1 + padding;
+            let mut grid: Vec<Vec<char>> = (0..height)
+                .map(|y| (0..width).map(|x| junk[(y * 11 + x) as usize]).collect())
+                .collect();
+            for (index, &(x, y)) in tiles.iter().enumerate() {
+                let (previous_x, previous_y) = tiles[(index + tiles.len() - 1) % tiles.len()];
+                let (next_x, next_y) = tiles[(index + 1) % tiles.len()];
+                grid[y as usize][x as usize] = pipe_with_openings(vec![(previous_x - x, previous_y - y), (next_x - x, next_y - y)]);
+            }
+            let (start_x, start_y) = tiles[start.index(tiles.len())];
+            grid[start_y as usize][start_x as usize] = 'S';
+            for (dx, dy) in DIRECTIONS {
+                let (x, y) = (start_x + dx, start_y + dy);
+                if x >= 0 && y >= 0 && x < width && y < height && !tiles.contains(&(x, y)) && openings(grid[y as usize][x as usize]).contains(&(-dx, -dy)) {
+                    grid[y as usize][x as usize] = '.';
+                }
+            }
+            return grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+        })
+    }
+
+    /// Walks the loop with a breadth-first search from the start, and finds the tiles it encloses by flood
+    /// filling the grid at three times the resolution, where there's room to squeeze between pipes.
+    /// Returns the distance to the farthest tile of the loop and the number of enclosed tiles.
+    fn loop_oracle(input: &str) -> (usize, usize) {
+        let mut grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+        let (width, height) = (grid[0].len() as i32, grid.len() as i32);
+        let tile = |grid: &Vec<Vec<char>>, x: i32, y: i32| -> char {
+            if x < 0 || y < 0 || x >= width || y >= height { '.' } else { grid[y as usize][x as usize] }
+        };
+        let (start_x, start_y) = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .find(|(x, y)| tile(&grid, *x, *y) == 'S')
+            .unwrap();
+        let start_openings = DIRECTIONS.into_iter()
+            .filter(|(dx, dy)| openings(tile(&grid, start_x + dx, start_y + dy)).contains(&(-dx, -dy)))
+            .collect();
+        grid[start_y as usize][start_x as usize] = pipe_with_openings(start_openings);
+
+        let mut distances = HashMap::from([((start_x, start_y), 0)]);
+        let mut queue = VecDeque::from([(start_x, start_y)]);
+        while let Some((x, y)) = queue.pop_front() {
+            for (dx, dy) in openings(tile(&grid, x, y)) {
+                if !distances.contains_key(&(x + dx, y + dy)) {
+                    distances.insert((x + dx, y + dy), distances[&(x, y)] + 1);
+                    queue.push_back((x + dx, y + dy));
+                }
+            }
+        }
+
+        // Every tile becomes a 3x3 block, with a border of one around the whole grid to flood fill from.
+        let mut walls = HashSet::new();
+        for &(x, y) in distances.keys() {
+            let (center_x, center_y) = (3 * x + 2, 3 * y + 2);
+            walls.insert((center_x, center_y));
+            walls.extend(openings(tile(&grid, x, y)).into_iter().map(|(dx, dy)| (center_x + dx, center_y + dy)));
+        }
+        let mut outside = HashSet::from([(0, 0)]);
+        let mut queue = VecDeque::from([(0, 0)]);
+        while let Some((x, y)) = queue.pop_front() {
+            for (dx, dy) in DIRECTIONS {
+                let next = (x + dx, y + dy);
+                let in_bounds = next.0 >= 0 && next.1 >= 0 && next.0 <= 3 * width + 1 && next.1 <= 3 * height + 1;
+                if in_bounds && !walls.contains(&next) && outside.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        let enclosed = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|(x, y)| !distances.contains_key(&(*x, *y)) && !outside.contains(&(3 * x + 2, 3 * y + 2)))
+            .count();
+        return (*distances.values().max().unwrap(), enclosed);
+    }
+
+    proptest! {
+        #[test]
+        fn test_parts_match_oracle(input in pipe_grid()) {
+            let (farthest, enclosed) = loop_oracle(&input);
+            prop_assert_eq!(part1(&input), Ok(farthest));
+            prop_assert_eq!(part2(&input), Ok(enclosed));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example1.txt, run with --include-ignored"]
     fn test_part1() {
-        let input1 = include_str!("../resources/example1.txt");
-        assert_eq!(part1(input1), 4);
+        let input1 = &example!("example1.txt");
+        assert_eq!(part1(input1), Ok(4));
     }
 
     #[test]
+    #[ignore = "needs resources/example2.txt, run with --include-ignored"]
     fn test_part1_other() {
-        let input2 = include_str!("../resources/example2.txt");
-        assert_eq!(part1(input2), 8);
+        let input2 = &example!("example2.txt");
+        assert_eq!(part1(input2), Ok(8));
     }
 
     #[test]
-    fn test_part2_a() { assert_eq!(part2(include_str!("../resources/example3.txt")), 4) }
+    #[ignore = "needs resources/example3.txt, run with --include-ignored"]
+    fn test_part2_a() { assert_eq!(part2(&example!("example3.txt")), Ok(4)) }
+
+    #[test]
+    #[ignore = "needs resources/example4.txt, run with --include-ignored"]
+    fn test_part2_b() { assert_eq!(part2(&example!("example4.txt")), Ok(8)) }
 
     #[test]
-    fn test_part2_b() { assert_eq!(part2(include_str!("../resources/example4.txt")), 8) }
+    #[ignore = "needs resources/example5.txt, run with --include-ignored"]
+    fn test_part2_c() { assert_eq!(part2(&example!("example5.txt")), Ok(10)) }
 
     #[test]
-    fn test_part2_c() { assert_eq!(part2(include_str!("../resources/example5.txt")), 10) }
+    fn test_pipe_loop() {
+        let matrix = parse(".S--7\n.|..|\n.L7.|\n..L-J").unwrap();
+        assert_eq!(matrix.pipe_loop().unwrap(), [
+            Point::new(1, 0),
+            Point::new(2, 0),
+            Point::new(3, 0),
+            Point::new(4, 0),
+            Point::new(4, 1),
+            Point::new(4, 2),
+            Point::new(4, 3),
+            Point::new(3, 3),
+            Point::new(2, 3),
+            Point::new(2, 2),
+            Point::new(1, 2),
+            Point::new(1, 1),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse(".S-7.\n.|.|.\n.L-J.").unwrap().cells.to_string(), ".S-7.\n.|.|.\n.L-J.");
+        assert_eq!(parse(".S-7.\n.|.x.").unwrap_err(), ParseError::new(2, 4, "x", "invalid pipe"));
+        assert_eq!(parse(".F-7.\n.L-J.").unwrap_err().message, "expected a start tile `S`");
+        assert_eq!(parse(".S-7.\n.L-S.").unwrap_err(), ParseError::new(2, 4, "S", "expected a single start tile `S`"));
+    }
 
     #[test]
-    fn test_next() {
-        let input1 = include_str!("../resources/example1.txt");
-        let matrix = parse(input1);
-        let mut matrix_iter = matrix.into_iter();
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 2, y: 1 }, Pipe::Horizontal));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 3, y: 1 }, Pipe::SW));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 3, y: 2 }, Pipe::Vertical));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 3, y: 3 }, Pipe::NW));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 2, y: 3 }, Pipe::Horizontal));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 1, y: 3 }, Pipe::NE));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 1, y: 2 }, Pipe::Vertical));
-        assert_eq!(matrix_iter.next().unwrap(), (IVec2 { x: 1, y: 1 }, Pipe::Start));
+    fn test_not_a_loop() {
+        assert_eq!(part1("S"), Err(not_a_loop()));
+        assert_eq!(part2(".S-7.\n.|...\n.L-J."), Err(not_a_loop()));
+        // A pipe branching off at the start tile.
+        assert_eq!(part2("-S-7.\n.|.|.\n.L-J."), Err(not_a_loop()));
     }
 
     #[test]
     fn test_is_connected_north_of() {
         // [p1, p2]   | [7-]
         // [p3, p4]   | [L7]
-        let p1 = IVec2 { x: 0, y: 0 };
-        let p2 = IVec2 { x: 1, y: 0 };
-        let p3 = IVec2 { x: 0, y: 1 };
-        let p4 = IVec2 { x: 1, y: 1 };
+        let p1 = Point::new(0, 0);
+        let p2 = Point::new(1, 0);
+        let p3 = Point::new(0, 1);
+        let p4 = Point::new(1, 1);
         let matrix = Matrix {
-            cells: vec![
+            cells: Grid::from_rows(vec![
                 vec![Pipe::SW, Pipe::Horizontal],
                 vec![Pipe::NE, Pipe::SW],
-            ],
-            start: IVec2 { x: 0, y: 0 },
+            ]),
+            start: Point::new(0, 0),
         };
         assert!(matrix.is_connected(p1, p3));
         assert!(matrix.is_connected(p3, p1));
@@ -1,7 +1,8 @@
-use day10::{part1, part2};
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../resources/input.txt");
-    println!("part 1: {}", part1(input));
-    println!("part 2: {}", part2(input));
+use aoc_common::inputs;
+use day10::Day10;
+
+fn main() -> ExitCode {
+    aoc_common::run::<Day10>(inputs!())
 }
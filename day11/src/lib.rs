@@ -1,58 +1,85 @@
 use std::cmp::{max, min};
-use std::collections::HashSet;
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> usize {
-    let galaxy_map = parse(input);
-    let mapped_galaxies: Vec<(usize, usize)> = galaxy_map.galaxies.iter().map(|(x, y)| {
-        let step_y = galaxy_map.empty_rows.iter().filter(|row_y| row_y < &y).count();
-        let step_x = galaxy_map.empty_columns.iter().filter(|col_x| col_x < &x).count();
-        (x + step_x, y + step_y)
-    }).collect();
-    return mapped_galaxies.iter().combinations(2)
-        .map(|pair| manhattan_distance(*pair[0], *pair[1]))
-        .sum();
+use aoc_common::{checked, ParseError, Solution, SolveError};
+use aoc_grid::Grid;
+
+pub struct Day11;
+
+/// How many rows or columns every empty row or column of the image expands into.
+#[derive(Debug, Copy, Clone)]
+pub struct ExpansionFactor(pub usize);
+
+impl Default for ExpansionFactor {
+    fn default() -> Self {
+        ExpansionFactor(1_000_000)
+    }
+}
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    type Params = ExpansionFactor;
+    type Input<'a> = GalaxyMap;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<GalaxyMap, ParseError> {
+        parse(input)
+    }
+
+    fn part1(galaxy_map: &GalaxyMap, _: &ExpansionFactor) -> Result<usize, SolveError> {
+        return distances(galaxy_map, 2);
+    }
+
+    fn part2(galaxy_map: &GalaxyMap, &ExpansionFactor(n_times): &ExpansionFactor) -> Result<usize, SolveError> {
+        return distances(galaxy_map, n_times);
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day11::solve_part1(input)
+}
+
+pub fn part2(input: &str, n_times: usize) -> Result<usize, SolveError> {
+    Day11::part2(&parse(input)?, &ExpansionFactor(n_times))
 }
 
-pub fn part2(input: &str, n_times: usize) -> usize {
-    let galaxy_map = parse(input);
-    let mapped_galaxies: Vec<(usize, usize)> = galaxy_map.galaxies.iter().map(|(x, y)| {
-        // We subtract one to account for _replacing_ the row.
-        let step_y = galaxy_map.empty_rows.iter().filter(|row_y| row_y < &y).count() * (n_times-1);
-        let step_x = galaxy_map.empty_columns.iter().filter(|col_x| col_x < &x).count() * (n_times-1);
-        (x + step_x, y + step_y)
-    }).collect();
-    return mapped_galaxies.iter().combinations(2)
+/// The sum of the distances between all pairs of galaxies, once every empty row and column has
+/// become `n_times` rows or columns.
+fn distances(galaxy_map: &GalaxyMap, n_times: usize) -> Result<usize, SolveError> {
+    // The empty rows before a galaxy are _replaced_, so they are taken out before adding the expanded ones.
+    let expand = |position: usize, empty: &[usize]| {
+        let n_empty = empty.iter().filter(|other| **other < position).count();
+        return checked::add(position - n_empty, checked::mul(n_empty, n_times)?);
+    };
+    let mapped_galaxies: Vec<(usize, usize)> = galaxy_map.galaxies.iter()
+        .map(|(x, y)| Ok((expand(*x, &galaxy_map.empty_columns)?, expand(*y, &galaxy_map.empty_rows)?)))
+        .collect::<Result<_, SolveError>>()?;
+    return checked::sum(mapped_galaxies.iter().combinations(2)
         .map(|pair| manhattan_distance(*pair[0], *pair[1]))
-        .sum::<usize>();
+        .collect::<Result<Vec<usize>, SolveError>>()?);
 }
 
-fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
-    return (max(a.0, b.0) - min(a.0, b.0)) +  (max(a.1, b.1) - min(a.1, b.1));
+fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> Result<usize, SolveError> {
+    return checked::add(max(a.0, b.0) - min(a.0, b.0), max(a.1, b.1) - min(a.1, b.1));
 }
 
-fn parse(input: &str) -> GalaxyMap {
-    let mut empty_rows: HashSet<usize> = (0..input.lines().count()).collect();
-    let mut empty_columns: HashSet<usize> = (0..input.lines().next().unwrap().chars().count()).collect();
-    let mut galaxies: Vec<(usize, usize)> = vec![];
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, c)| {
-            if c == '#' {
-                galaxies.push((x, y));
-                if empty_rows.contains(&y) { empty_rows.remove(&y); }
-                if empty_columns.contains(&x) { empty_columns.remove(&x); }
-            }
-        });
-    });
-    let mut empty_columns: Vec<usize> = empty_columns.iter().cloned().collect();
-    empty_columns.sort();
-    let mut empty_rows: Vec<usize> = empty_rows.iter().cloned().collect();
-    empty_rows.sort();
-    return GalaxyMap { galaxies, empty_columns, empty_rows };
+fn parse(input: &str) -> Result<GalaxyMap, ParseError> {
+    let image = Grid::parse(input, |c| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err("expected `.` or `#`"),
+    })?;
+    let galaxies: Vec<(usize, usize)> = image.iter()
+        .filter(|(_, galaxy)| **galaxy)
+        .map(|(point, _)| (point.x as usize, point.y as usize))
+        .collect();
+    let empty_rows: Vec<usize> = image.rows().positions(|row| !row.contains(&true)).collect();
+    let empty_columns: Vec<usize> = image.columns().positions(|mut column| !column.any(|galaxy| *galaxy)).collect();
+    return Ok(GalaxyMap { galaxies, empty_columns, empty_rows });
 }
 
 #[derive(Debug)]
-struct GalaxyMap {
+pub struct GalaxyMap {
     galaxies: Vec<(usize, usize)>,
     empty_rows: Vec<usize>,
     empty_columns: Vec<usize>,
@@ -60,31 +87,88 @@ struct GalaxyMap {
 
 #[cfg(test)]
 mod test_day11 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
     use super::*;
 
+    /// Images with few galaxies, so there are empty rows and columns to expand.
+    fn image() -> impl Strategy<Value = Vec<Vec<char>>> {
+        let cell = prop::sample::select(vec!['.', '.', '.', '.', '#']);
+        return (1..8usize, 1..8usize).prop_flat_map(move |(width, height)| {
+            prop::collection::vec(prop::collection::vec(cell.clone(), width), height)
+        });
+    }
+
+    /// Expands the image itself, repeating every empty row and column `factor` times, and measures the
+    /// distances in the expanded image.
+    fn distances_oracle(image: &[Vec<char>], factor: usize) -> usize {
+        let rows: Vec<Vec<char>> = image.iter()
+            .flat_map(|row| vec![row.clone(); if row.contains(&'#') { 1 } else { factor }])
+            .collect();
+        let columns: Vec<usize> = (0..image[0].len())
+            .flat_map(|x| vec![x; if image.iter().any(|row| row[x] == '#') { 1 } else { factor }])
+            .collect();
+        let galaxies: Vec<(usize, usize)> = rows.iter().enumerate()
+            .flat_map(|(y, row)| columns.iter().enumerate().filter(|(_, x)| row[**x] == '#').map(move |(x, _)| (x, y)))
+            .collect();
+        return galaxies.iter().enumerate()
+            .flat_map(|(index, a)| galaxies[index + 1..].iter().map(|b| a.0.abs_diff(b.0) + a.1.abs_diff(b.1)))
+            .sum();
+    }
+
+    proptest! {
+        #[test]
+        fn test_parts_match_oracle(image in image(), factor in 1..5usize) {
+            let input = image.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+            prop_assert_eq!(part1(&input), Ok(distances_oracle(&image, 2)));
+            prop_assert_eq!(part2(&input, factor), Ok(distances_oracle(&image, factor)));
+        }
+    }
+
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part1() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part1(input), 374);
+        let input = &example!("example.txt");
+        assert_eq!(part1(input), Ok(374));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part2_10() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part2(input, 10), 1030);
+        let input = &example!("example.txt");
+        assert_eq!(part2(input, 10), Ok(1030));
     }
 
     #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
     fn test_part2_100() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part2(input, 100), 8410);
+        let input = &example!("example.txt");
+        assert_eq!(part2(input, 100), Ok(8410));
+    }
+
+    const IMAGE: &str = "#....\n.....\n..#..\n.....\n....#";
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(part1(IMAGE), Ok(24));
+        assert_eq!(part2(IMAGE, usize::MAX), Err(checked::overflow::<usize>()));
+        assert_eq!(part2(IMAGE, usize::MAX / 8), Err(checked::overflow::<usize>()));
+        assert_eq!(part2(IMAGE, usize::MAX / 16), Ok(1 << 63));
+        assert_eq!(part2("#.#", 0), Ok(1));
     }
 
     #[test]
     fn test_parse() {
-        let parsed = parse(include_str!("../resources/example.txt"));
-        assert_eq!(parsed.galaxies.len(), 9);
+        let parsed = parse(IMAGE).unwrap();
+        assert_eq!(parsed.galaxies.len(), 3);
         assert_eq!(parsed.empty_rows.len(), 2);
-        assert_eq!(parsed.empty_columns.len(), 3);
+        assert_eq!(parsed.empty_columns.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse("...#\n.#o.").unwrap_err(), ParseError::new(2, 3, "o", "expected `.` or `#`"));
     }
 }
\ No newline at end of file
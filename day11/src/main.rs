@@ -1,7 +1,8 @@
-use day11::{part1, part2};
+use std::process::ExitCode;
 
-fn main() {
-    let input = include_str!("../resources/input.txt");
-    println!("Part 1: {}", part1(input));
-    println!("Part 2: {}", part2(input, 1_000_000));
+use aoc_common::inputs;
+use day11::Day11;
+
+fn main() -> ExitCode {
+    aoc_common::run::<Day11>(inputs!())
 }
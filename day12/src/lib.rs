@@ -1,91 +1,180 @@
-use std::collections::HashMap;
-
-type Cache = HashMap<(Vec<char>, Vec<usize>), usize>;
-
-pub fn part1(input: &str) -> usize {
-    return input.lines().map(parse_line)
-        .map(|(pattern, springs)| process_row_string_pattern(pattern, springs))
-        .sum();
-}
-
-pub fn part2(input: &str) -> usize {
-    return input.lines().map(parse_line)
-        .map(|(pattern, springs)| ([pattern].repeat(5).join("?"), springs.repeat(5)))
-        .map(|(pattern, springs)| process_row_string_pattern(pattern.as_str(), springs))
-        .sum();
-}
-
-fn process_row_string_pattern(pattern: &str, springs: Vec<usize>) -> usize {
-    return process_row(pattern.chars().collect::<Vec<_>>().as_slice(), springs.as_slice(), &mut HashMap::new());
-}
-
-fn process_row(pattern: &[char], springs: &[usize], cache: &mut Cache) -> usize {
-    let cache_key = (pattern.to_vec(), springs.to_vec());
-    if let Some(&result) = cache.get(&cache_key) { return result; }
-    if springs.is_empty() {
-        return !pattern.contains(&'#') as usize;
-    }
-    if pattern.is_empty() {
-        return 0;
-    }
-    let result = match pattern[0] {
-        '.' => process_row(&pattern[1..], springs, cache),
-        '#' => process_spring(pattern, springs, cache),
-        '?' => process_spring(pattern, springs, cache) + process_row(&pattern[1..], springs, cache),
-        _ => panic!("Illegal character"),
-    };
-    cache.insert(cache_key, result);
-    return result;
-}
-
-fn process_spring(pattern: &[char], springs: &[usize], cache: &mut Cache) -> usize {
-    let spring_length = springs[0];
-    if pattern.len() < spring_length || pattern[0..spring_length].contains(&'.') {
-        return 0;
-    }
-    if pattern.len() == spring_length {
-        return (springs.len() == 1) as usize;
-    }
-    if pattern[spring_length] == '#' {
-        return 0;
-    }
-    return process_row(&pattern[spring_length+1..], &springs[1..], cache);
-}
-
-fn parse_line(line: &str) -> (&str, Vec<usize>) {
-    let mut iter = line.splitn(2, ' ');
-    let pattern= iter.next().expect("A pattern of hotsprings");
-    let springs: Vec<usize>  = iter.next().expect("A description of broken springs")
-        .split(',')
-        .map(|x| x.parse::<usize>().expect("Valid springs"))
-        .collect();
-    return (pattern, springs);
-}
-
-#[cfg(test)]
-mod test_day12 {
-    use super::*;
-
-    #[test]
-    fn test_part1() {
-        let input = include_str!("../resources/example.txt");
-        assert_eq!(part1(input), 21);
-    }
-
-    #[test]
-    fn test_part1_oneline() {
-        assert_eq!(part1("???.### 1,1,3"), 1);
-        assert_eq!(part1(".??..??...?##. 1,1,3"), 4);
-        assert_eq!(part1("?#?#?#?#?#?#?#? 1,3,1,6"), 1);
-        assert_eq!(part1("????.#...#... 4,1,1"), 1);
-        assert_eq!(part1("????.######..#####. 1,6,5"), 4);
-        assert_eq!(part1("?###???????? 3,2,1"), 10);
-    }
-
-    #[test]
-    fn test_part2_oneline() {
-        assert_eq!(part2("???.### 1,1,3"), 1);
-        assert_eq!(part2(".??..??...?##. 1,1,3"), 16384);
-        assert_eq!(part2("?###???????? 3,2,1"), 506250);
-    }
+use tracing::debug;
+
+use aoc_common::parse::{parse_lines, parse_number};
+use aoc_common::{checked, parallel, ParseError, Solution, SolveError};
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Params = ();
+    /// Every row as its pattern of springs and the sizes of the damaged groups.
+    type Input<'a> = Vec<(&'a str, Vec<usize>)>;
+    type Answer = usize;
+
+    fn parse(input: &str) -> Result<Vec<(&str, Vec<usize>)>, ParseError> {
+        parse_lines(input, parse_line)
+    }
+
+    fn part1(rows: &Vec<(&str, Vec<usize>)>, _: &()) -> Result<usize, SolveError> {
+        return parallel::try_sum(rows, |(pattern, springs)| process_row_string_pattern(pattern, springs.clone()));
+    }
+
+    fn part2(rows: &Vec<(&str, Vec<usize>)>, _: &()) -> Result<usize, SolveError> {
+        return parallel::try_sum(rows, |(pattern, springs)| {
+            process_row_string_pattern(&[*pattern].repeat(5).join("?"), springs.repeat(5))
+        });
+    }
+}
+
+pub fn part1(input: &str) -> Result<usize, SolveError> {
+    Day12::solve_part1(input)
+}
+
+pub fn part2(input: &str) -> Result<usize, SolveError> {
+    Day12::solve_part2(input)
+}
+
+fn process_row_string_pattern(pattern: &str, springs: Vec<usize>) -> Result<usize, SolveError> {
+    let arrangements = process_row(pattern.as_bytes(), &springs)?;
+    debug!(pattern, ?springs, arrangements, "row");
+    return Ok(arrangements);
+}
+
+/// The arrangements of `springs` in `pattern`, counted from the end of the row backwards: the arrangements
+/// of the rest of the row from a pattern index on, by the group index they start with, follow from those
+/// further on.
+fn process_row(pattern: &[u8], springs: &[usize]) -> Result<usize, SolveError> {
+    // How many springs from an index on could be damaged, so whether a group fits there.
+    let mut runs = vec![0; pattern.len() + 1];
+    for index in (0..pattern.len()).rev() {
+        runs[index] = if pattern[index] == b'.' { 0 } else { runs[index + 1] + 1 };
+    }
+    // Only the end of the row remains: the arrangements are 1 without groups left, 0 otherwise.
+    let mut arrangements = vec![vec![0; springs.len() + 1]; pattern.len() + 1];
+    arrangements[pattern.len()][springs.len()] = 1;
+    for index in (0..pattern.len()).rev() {
+        for group in 0..=springs.len() {
+            // The spring at `index` is operational.
+            let mut total = if pattern[index] == b'#' { 0 } else { arrangements[index + 1][group] };
+            // Or it starts the next group, which is followed by the end of the row or an operational spring.
+            if let Some(&length) = springs.get(group).filter(|length| runs[index] >= **length) {
+                let end = index + length;
+                let after = match pattern.get(end) {
+                    None => (group + 1 == springs.len()) as usize,
+                    Some(b'#') => 0,
+                    Some(_) => arrangements[end + 1][group + 1],
+                };
+                total = checked::add(total, after)?;
+            }
+            arrangements[index][group] = total;
+        }
+    }
+    return Ok(arrangements[0][0]);
+}
+
+fn parse_line(line: &str) -> Result<(&str, Vec<usize>), ParseError> {
+    let (pattern, springs) = line.split_once(' ')
+        .ok_or_else(|| ParseError::locate(line, line, "expected `<pattern> <damaged groups>`"))?;
+    if let Some((index, c)) = pattern.char_indices().find(|(_, c)| !matches!(c, '.' | '#' | '?')) {
+        return Err(ParseError::locate(line, &pattern[index..index + c.len_utf8()], "expected `.`, `#` or `?`"));
+    }
+    let springs: Vec<usize> = springs
+        .split(',')
+        .map(|x| parse_number(line, x))
+        .collect::<Result<_, _>>()?;
+    return Ok((pattern, springs));
+}
+
+#[cfg(test)]
+mod test_day12 {
+    use proptest::prelude::*;
+
+    use aoc_common::example;
+
+    use super::*;
+
+    /// Rows of springs with damaged groups that may or may not fit the pattern.
+    fn row() -> impl Strategy<Value = (String, Vec<usize>)> {
+        return ("[.#?]{1,14}", prop::collection::vec(1..5usize, 1..5));
+    }
+
+    /// Tries every way of filling in the unknown springs and counts those with the right groups.
+    fn arrangements_oracle(pattern: &str, groups: &[usize]) -> usize {
+        let unknown: Vec<usize> = pattern.char_indices().filter(|(_, c)| *c == '?').map(|(index, _)| index).collect();
+        return (0..1usize << unknown.len())
+            .filter(|filling| {
+                let mut springs: Vec<char> = pattern.chars().collect();
+                for (bit, index) in unknown.iter().enumerate() {
+                    springs[*index] = if filling & (1 << bit) != 0 { '#' } else { '.' };
+                }
+                let found: Vec<usize> = springs.split(|c| *c == '.').map(|group| group.len()).filter(|len| *len > 0).collect();
+                return found == groups;
+            })
+            .count();
+    }
+
+    proptest! {
+        #[test]
+        fn test_process_row_matches_oracle((pattern, groups) in row()) {
+            prop_assert_eq!(process_row_string_pattern(&pattern, groups.clone()), Ok(arrangements_oracle(&pattern, &groups)));
+        }
+
+        #[test]
+        fn test_part1_matches_oracle(rows in prop::collection::vec(row(), 1..5)) {
+            let input = rows.iter()
+                .map(|(pattern, groups)| format!("{pattern} {}", groups.iter().map(usize::to_string).collect::<Vec<_>>().join(",")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let expected: usize = rows.iter().map(|(pattern, groups)| arrangements_oracle(pattern, groups)).sum();
+            prop_assert_eq!(part1(&input), Ok(expected));
+        }
+    }
+
+    #[test]
+    #[ignore = "needs resources/example.txt, run with --include-ignored"]
+    fn test_part1() {
+        let input = &example!("example.txt");
+        assert_eq!(part1(input), Ok(21));
+    }
+
+    #[test]
+    fn test_part1_oneline() {
+        assert_eq!(part1("???.### 1,1,3"), Ok(1));
+        assert_eq!(part1(".??..??...?##. 1,1,3"), Ok(4));
+        assert_eq!(part1("?#?#?#?#?#?#?#? 1,3,1,6"), Ok(1));
+        assert_eq!(part1("????.#...#... 4,1,1"), Ok(1));
+        assert_eq!(part1("????.######..#####. 1,6,5"), Ok(4));
+        assert_eq!(part1("?###???????? 3,2,1"), Ok(10));
+    }
+
+    #[test]
+    fn test_part2_oneline() {
+        assert_eq!(part2("???.### 1,1,3"), Ok(1));
+        assert_eq!(part2(".??..??...?##. 1,1,3"), Ok(16384));
+        assert_eq!(part2("?###???????? 3,2,1"), Ok(506250));
+    }
+
+    #[test]
+    fn test_long_row() {
+        let row = format!("{}#?{} 2,1", ".".repeat(100_000), "?.".repeat(100_000));
+        assert_eq!(part1(&row), Ok(99_999));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = format!("{} {}", "?".repeat(200), ["1"; 50].join(","));
+        assert_eq!(part1(&input), Err(checked::overflow::<usize>()));
+        // Every row has C(71, 25), about 9.96e18, arrangements, which fits, but two of them do not.
+        let row = format!("{} {}", "?".repeat(95), ["1"; 25].join(","));
+        assert_eq!(part1(&row), Ok(9_964_327_949_818_248_552));
+        assert_eq!(part1(&format!("{row}\n{row}")), Err(checked::overflow::<usize>()));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(Day12::parse("???.### 1,1,3\n.??..x?...?##. 1,1,3").unwrap_err(), ParseError::new(2, 6, "x", "expected `.`, `#` or `?`"));
+        assert_eq!(Day12::parse("???.### 1,,3").unwrap_err(), ParseError::new(1, 11, "", "expected a number"));
+        assert_eq!(Day12::parse("???.###").unwrap_err().message, "expected `<pattern> <damaged groups>`");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use aoc_common::Solution;
+
+/// Parses `data` and solves both parts of it, skipping data that is not UTF-8 since the days only
+/// take text. Whatever the input, this must return: errors are fine, panics and overflows are not.
+pub fn solve<S: Solution>(data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(parsed) = S::parse(input) else {
+        return;
+    };
+    let params = S::Params::default();
+    let _ = S::part1(&parsed, &params);
+    let _ = S::part2(&parsed, &params);
+}